@@ -5,6 +5,7 @@ use linguist_build::{
 
 fn main() {
     linguist_build::Config::new()
+        .generate_container("container.rs")
         .add_definition(Definition {
             name: "languages.rs".to_string(),
             kind: Kind::Languages,
@@ -25,5 +26,6 @@ fn main() {
             kind: Kind::Documentation,
             location: Location::URL(GITHUB_LINGUIST_DOCUMENTATION_URL.to_string()),
         })
-        .generate();
+        .generate()
+        .unwrap_or_else(|err| panic!("{}", err));
 }
@@ -1,16 +1,19 @@
 use std::{
-    io::Write,
+    collections::BTreeMap,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use linguist::{
+    error::LinguistError,
     github::{
         load_github_documentation, load_github_linguist_heuristics, load_github_linguist_languages,
-        load_github_vendors,
+        load_github_linguist_languages_dir, load_github_vendors, load_language_urls,
     },
     resolver::{HeuristicRule, Language},
+    serde::list_yaml_files,
 };
-use tempfile::tempdir;
+use tempfile::{tempdir, TempDir};
 
 pub static GITHUB_LINGUIST_LANGUAGES_URL: &str =
     "https://raw.githubusercontent.com/github-linguist/linguist/master/lib/linguist/languages.yml";
@@ -21,6 +24,13 @@ pub static GITHUB_LINGUIST_VENDORS_URL: &str =
 pub static GITHUB_LINGUIST_DOCUMENTATION_URL: &str =
     "https://raw.githubusercontent.com/github-linguist/linguist/master/lib/linguist/documentation.yml";
 
+/// The default time a single download may take, see [`Config::http_timeout`].
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+/// The default number of times a failed download is retried, see [`Config::retries`].
+pub const DEFAULT_RETRIES: u8 = 2;
+/// The delay before the first retry of a failed download; it doubles with every further retry.
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
 /// The `Config` is used to configure the build process. It can be used to specify the `output path` and
 /// the `definitions` to be generated.
 #[derive(Clone, PartialEq, Eq)]
@@ -29,19 +39,53 @@ pub struct Config {
     out_path: PathBuf,
     /// The `definitions` are used to specify which definitions should be generated.
     definitions: Vec<Definition>,
+    /// The `cache_dir` is used to keep downloaded definitions across builds, see
+    /// [`Config::with_cache`].
+    cache_dir: Option<PathBuf>,
+    /// The `cache_ttl` is used to specify how long a cached definition is used before it is
+    /// downloaded again, see [`Config::cache_ttl`].
+    cache_ttl: Option<Duration>,
+    /// The `combined_output` is used to write all definitions into a single file, see
+    /// [`Config::combined_output`].
+    combined_output: Option<String>,
+    /// The `container_output` is used to write a function that builds a container of all
+    /// definitions, see [`Config::generate_container`].
+    container_output: Option<String>,
+    /// The `http_timeout` is used to limit how long a single download may take, see
+    /// [`Config::http_timeout`].
+    http_timeout: Duration,
+    /// The `retries` are used to specify how often a failed download is retried, see
+    /// [`Config::retries`].
+    retries: u8,
+    /// The `headers` are sent with every download, see [`Config::header`].
+    headers: Vec<(String, String)>,
 }
 
 impl Default for Config {
     fn default() -> Self {
+        Config::with_out_path(PathBuf::from(std::env::var_os("OUT_DIR").unwrap()))
+    }
+}
+
+impl Config {
+    /// Used internally to create a `Config` with default options that writes to the given path.
+    fn with_out_path(out_path: PathBuf) -> Self {
         Config {
-            out_path: PathBuf::from(std::env::var_os("OUT_DIR").unwrap()),
+            out_path,
             definitions: vec![],
+            cache_dir: None,
+            cache_ttl: None,
+            combined_output: None,
+            container_output: None,
+            http_timeout: DEFAULT_HTTP_TIMEOUT,
+            retries: DEFAULT_RETRIES,
+            headers: vec![],
         }
     }
 }
 
 /// A `Definition` is used to specify the `name`, [`Location`], and the [`Kind`] of an artifact
-/// to generate. The `Location` can either be a `URL` or a `Path`. The `Kind` specifies the type of
+/// to generate. The `Location` can either be a `URL`, a `Path`, or a `Dir`. The `Kind` specifies the type of
 /// artifact to generate, e.g., Languages, Heuristics, Vendors, or Documentation.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Definition {
@@ -59,6 +103,10 @@ pub enum Location {
     /// The `URL` variant is used to specify the URL to the respective [`Definition`]. It will be
     /// downloaded from the given URL.
     URL(String),
+    /// The `Dir` variant is used to specify a directory whose YAML files (`*.yml` and `*.yaml`)
+    /// together make up the respective [`Definition`]. The files are read in alphabetical order;
+    /// languages defined in several files are overridden by the later file.
+    Dir(PathBuf),
 }
 
 /// Kind is used to specify the type of artifact to generate, e.g., Languages, Heuristics, Vendors,
@@ -69,6 +117,8 @@ pub enum Kind {
     Heuristics,
     Vendors,
     Documentation,
+    /// An optional mapping of language names to reference URLs, read from a `name: url` YAML file.
+    LanguageUrls,
 }
 
 impl Config {
@@ -83,129 +133,680 @@ impl Config {
         self
     }
 
-    /// Used internally to download a definition from the given `url` and write it to the given
-    /// `out_dir`.
-    fn download_from_url(&self, out_dir: &Path, url: &str) -> Result<PathBuf, ()> {
-        match reqwest::blocking::get(url) {
-            Ok(result) => {
-                let path = out_dir.join("file.yml");
-                let mut file = std::fs::File::create(path.clone()).expect("cannot create tempfile");
-                std::io::copy(
-                    &mut result
-                        .text()
-                        .expect("cannot unwrap response file")
-                        .as_bytes(),
-                    &mut file,
-                )
-                .expect("cannot copy reponse into file");
-                Ok(path)
-            }
-            Err(_) => Err(()),
+    /// Writes all definitions into the single file `name` instead of one file per definition, so
+    /// they can be included at once. Every definition is placed into its own module, named after
+    /// the file stem of the definition's name, e.g., `languages.rs` becomes `mod languages`, so
+    /// the generated symbols such as `LANGUAGES` and `VENDORS` never clash.
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::PathBuf;
+    ///
+    /// use linguist_build::{Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// let fixtures = PathBuf::from("../linguist/tests/fixtures/github");
+    /// Config::new()
+    ///     .combined_output("linguist.rs")
+    ///     .add_definition(Definition {
+    ///         name: "languages.rs".to_string(),
+    ///         location: Location::Path(fixtures.join("languages.yml")),
+    ///         kind: Kind::Languages,
+    ///     })
+    ///     .add_definition(Definition {
+    ///         name: "vendors.rs".to_string(),
+    ///         location: Location::Path(fixtures.join("vendor.yml")),
+    ///         kind: Kind::Vendors,
+    ///     })
+    ///     .generate()
+    ///     .unwrap();
+    ///
+    /// assert!(!out_dir.path().join("languages.rs").exists());
+    /// let content = std::fs::read_to_string(out_dir.path().join("linguist.rs")).unwrap();
+    /// assert!(content.contains("pub mod languages {"));
+    /// assert!(content.contains("pub mod vendors {"));
+    /// ```
+    pub fn combined_output(&mut self, name: impl Into<String>) -> &mut Self {
+        self.combined_output = Some(name.into());
+        self
+    }
+
+    /// Writes a `pub fn build_container() -> InMemoryLanguageContainer` into the file `name`, which
+    /// registers the languages and heuristic rules of all [`Kind::Languages`] and
+    /// [`Kind::Heuristics`] definitions. The file has to be included next to the generated
+    /// definitions; if a [`Config::combined_output`] is configured, the function is added to the
+    /// combined file instead.
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::PathBuf;
+    ///
+    /// use linguist_build::{Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// Config::new()
+    ///     .generate_container("container.rs")
+    ///     .add_definition(Definition {
+    ///         name: "languages.rs".to_string(),
+    ///         location: Location::Path(PathBuf::from("../linguist/tests/fixtures/github/languages.yml")),
+    ///         kind: Kind::Languages,
+    ///     })
+    ///     .generate()
+    ///     .unwrap();
+    ///
+    /// let content = std::fs::read_to_string(out_dir.path().join("container.rs")).unwrap();
+    /// assert!(content.contains("pub fn build_container()"));
+    /// assert!(content.contains(".languages(LANGUAGES.iter().copied())"));
+    /// ```
+    pub fn generate_container(&mut self, name: impl Into<String>) -> &mut Self {
+        self.container_output = Some(name.into());
+        self
+    }
+
+    /// Keeps downloaded definitions in the given directory, so they are not downloaded again on
+    /// every build. A cached definition is used until the [`Config::cache_ttl`] has expired; if
+    /// the download of an expired definition fails, e.g., while offline, the cached one is used
+    /// anyway.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use linguist_build::{BuildError, Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// let cache_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// // nothing listens on this port, so every download fails
+    /// let url = "http://127.0.0.1:9/vendor.yml";
+    /// let mut config = Config::new();
+    /// config
+    ///     .with_cache(cache_dir.path().to_path_buf())
+    ///     .cache_ttl(Duration::ZERO)
+    ///     .add_definition(Definition {
+    ///         name: "vendors.rs".to_string(),
+    ///         location: Location::URL(url.to_string()),
+    ///         kind: Kind::Vendors,
+    ///     });
+    /// assert!(matches!(config.generate(), Err(BuildError::Download { .. })));
+    ///
+    /// // an expired definition is used when the download fails
+    /// let cached = config.cache_path(url).unwrap();
+    /// std::fs::copy("../linguist/tests/fixtures/github/vendor.yml", &cached).unwrap();
+    /// assert!(config.generate().is_ok());
+    /// assert!(out_dir.path().join("vendors.rs").exists());
+    /// ```
+    pub fn with_cache(&mut self, dir: PathBuf) -> &mut Self {
+        self.cache_dir = Some(dir);
+        self
+    }
+
+    /// Sets how long a cached definition is used before it is downloaded again, see
+    /// [`Config::with_cache`]. By default, a cached definition never expires.
+    pub fn cache_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets how long a single download may take before it is aborted, so a hung connection cannot
+    /// stall the build. Defaults to [`DEFAULT_HTTP_TIMEOUT`].
+    pub fn http_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.http_timeout = timeout;
+        self
+    }
+
+    /// Sets how often a download is retried after a transient failure, i.e., a timeout, a
+    /// connection error, or a `429` or `5xx` status; the delay between the attempts doubles every
+    /// time. Other failures, e.g., a `404`, are not retried. Defaults to [`DEFAULT_RETRIES`].
+    ///
+    /// # Example
+    /// ```
+    /// use std::{io::{Read, Write}, net::TcpListener};
+    ///
+    /// use linguist_build::{BuildError, Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// // a server that is unavailable for the first request only
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let url = format!("http://{}/vendor.yml", listener.local_addr().unwrap());
+    /// std::thread::spawn(move || {
+    ///     let responses = [
+    ///         "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+    ///         "HTTP/1.1 200 OK\r\ncontent-length: 11\r\nconnection: close\r\n\r\n- ^vendor/\n",
+    ///         "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+    ///     ];
+    ///     for (stream, response) in listener.incoming().zip(responses) {
+    ///         let mut stream = stream.unwrap();
+    ///         let _ = stream.read(&mut [0; 1024]);
+    ///         stream.write_all(response.as_bytes()).unwrap();
+    ///     }
+    /// });
+    ///
+    /// let mut config = Config::new();
+    /// config.add_definition(Definition {
+    ///     name: "vendors.rs".to_string(),
+    ///     location: Location::URL(url.clone()),
+    ///     kind: Kind::Vendors,
+    /// });
+    /// config.retries(1).generate().unwrap();
+    ///
+    /// match config.retries(0).generate() {
+    ///     Err(BuildError::Download { url: failed, status, .. }) => {
+    ///         assert_eq!(failed, url);
+    ///         assert_eq!(status.map(|status| status.as_u16()), Some(503));
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    pub fn retries(&mut self, retries: u8) -> &mut Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Adds a header that is sent with the download of every [`Location::URL`] definition, e.g.,
+    /// to access definitions on a private GitHub Enterprise instance.
+    ///
+    /// # Example
+    /// ```
+    /// use std::{io::{Read, Write}, net::TcpListener};
+    ///
+    /// use linguist_build::{Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// // a server that only answers authorized requests
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let url = format!("http://{}/vendor.yml", listener.local_addr().unwrap());
+    /// std::thread::spawn(move || {
+    ///     let mut stream = listener.incoming().next().unwrap().unwrap();
+    ///     let mut request = [0; 1024];
+    ///     let len = stream.read(&mut request).unwrap();
+    ///     let request = String::from_utf8_lossy(&request[..len]).to_lowercase();
+    ///     let response = if request.contains("authorization: bearer secret")
+    ///         && request.contains("x-team: linguist")
+    ///     {
+    ///         "HTTP/1.1 200 OK\r\ncontent-length: 11\r\nconnection: close\r\n\r\n- ^vendor/\n"
+    ///     } else {
+    ///         "HTTP/1.1 401 Unauthorized\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+    ///     };
+    ///     stream.write_all(response.as_bytes()).unwrap();
+    /// });
+    ///
+    /// Config::new()
+    ///     .bearer_token("secret")
+    ///     .header("X-Team", "linguist")
+    ///     .add_definition(Definition {
+    ///         name: "vendors.rs".to_string(),
+    ///         location: Location::URL(url),
+    ///         kind: Kind::Vendors,
+    ///     })
+    ///     .generate()
+    ///     .unwrap();
+    /// ```
+    pub fn header(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Adds an `Authorization` header with the given bearer `token`, see [`Config::header`].
+    pub fn bearer_token(&mut self, token: impl AsRef<str>) -> &mut Self {
+        self.header("Authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    /// Returns the path at which the definition of the given `url` is cached, if a cache is
+    /// configured, see [`Config::with_cache`]. The file is named by a hash of the `url`.
+    pub fn cache_path(&self, url: &str) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|dir| dir.join(download_file_name(url)))
+    }
+
+    /// Used internally to check whether the given cached file exists and has not expired.
+    fn is_cache_fresh(&self, path: &Path) -> bool {
+        let modified = match std::fs::metadata(path).and_then(|meta| meta.modified()) {
+            Ok(modified) => modified,
+            _ => return false,
+        };
+
+        match self.cache_ttl {
+            Some(ttl) => modified.elapsed().is_ok_and(|age| age < ttl),
+            _ => true,
         }
     }
 
-    /// Generate a [`Language`] definition and writes it to the `out_path`.
-    fn generate_language(&self, name: &str, location: Location) {
-        let tmpdir = tempdir().expect("failed to create a tempdir");
-        let def_file = match location {
-            Location::URL(url) => self.download_from_url(tmpdir.path(), &url).unwrap(),
-            Location::Path(path) => path,
+    /// Used internally to download a definition from the given `url` and write it to the given
+    /// `out_dir`, or into the cache if one is configured. A response with an error status, e.g.,
+    /// an HTML error page, is not written.
+    fn download_from_url(&self, out_dir: &Path, url: &str) -> Result<PathBuf, BuildError> {
+        let cached = self.cache_path(url);
+        if let Some(cached) = cached.as_ref().filter(|path| self.is_cache_fresh(path)) {
+            return Ok(cached.clone());
+        }
+
+        let content = match self.fetch(url) {
+            Ok(content) => content,
+            Err(source) => match cached {
+                // an expired definition is better than none, e.g., while offline
+                Some(cached) if cached.is_file() => return Ok(cached),
+                _ => {
+                    return Err(BuildError::Download {
+                        url: url.to_string(),
+                        status: source.status(),
+                        source,
+                    })
+                }
+            },
         };
 
-        let data = load_github_linguist_languages(def_file).unwrap();
-        let mut entries: Vec<String> = Vec::new();
-        for item in data.iter() {
-            entries.push(write_language_definition(item));
+        if let Some(dir) = &self.cache_dir {
+            std::fs::create_dir_all(dir).map_err(|source| BuildError::Io {
+                path: dir.clone(),
+                source,
+            })?;
         }
+        let path = cached.unwrap_or_else(|| out_dir.join(download_file_name(url)));
+        std::fs::write(&path, content).map_err(|source| BuildError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(path)
+    }
+
+    /// Used internally to fetch the content of the given `url` with the configured headers,
+    /// retrying transient failures with an exponential backoff, see [`Config::retries`].
+    fn fetch(&self, url: &str) -> Result<String, reqwest::Error> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(self.http_timeout)
+            .build()?;
 
-        let target_path = self.out_path.clone();
-        let mut target_file = std::fs::File::create(target_path.join(name)).unwrap();
-        _ = target_file.write_all("use linguist::serde::StaticLanguage;\n\npub static LANGUAGES: &[&StaticLanguage] = &[\n".to_string().as_bytes());
-        for str in entries {
-            _ = target_file.write_all(format!("    {},\n", str).as_bytes());
+        let mut attempt = 0;
+        loop {
+            let mut request = client.get(url);
+            for (name, value) in self.headers.iter() {
+                request = request.header(name, value);
+            }
+            let result = request
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.text());
+            match result {
+                Err(err) if attempt < self.retries && is_transient(&err) => {
+                    std::thread::sleep(RETRY_BACKOFF * 2u32.pow(attempt.into()));
+                    attempt += 1;
+                }
+                result => return result,
+            }
         }
-        _ = target_file.write_all("];\n".to_string().as_bytes());
-        _ = target_file.flush();
     }
 
-    /// Generate a [`HeuristicRule`] definition and writes it to the `out_path`.
-    fn generate_heuristics(&self, name: &str, location: Location) {
-        let tmpdir = tempdir().expect("failed to create a tempdir");
-        let def_file = match location {
-            Location::URL(url) => self.download_from_url(tmpdir.path(), &url).unwrap(),
-            Location::Path(path) => path,
-        };
+    /// Used internally to determine the definition files of the given `location`. A `URL` is
+    /// downloaded into the given `out_dir`, a `Dir` is expanded into its YAML files.
+    fn definition_files(
+        &self,
+        out_dir: &Path,
+        location: Location,
+    ) -> Result<Vec<PathBuf>, BuildError> {
+        match location {
+            Location::URL(url) => Ok(vec![self.download_from_url(out_dir, &url)?]),
+            Location::Path(path) => Ok(vec![path]),
+            Location::Dir(dir) => {
+                list_yaml_files(&dir).map_err(|source| BuildError::Load { path: dir, source })
+            }
+        }
+    }
 
-        let data = load_github_linguist_heuristics(def_file).unwrap();
-        let mut entries: Vec<String> = Vec::new();
-        for item in data.iter() {
-            entries.push(write_heuristic_definition(item));
+    /// Used internally to load the [`Language`]s of the given `location`.
+    fn load_languages(&self, location: Location) -> Result<Vec<Language>, BuildError> {
+        match location {
+            Location::Dir(dir) => load_github_linguist_languages_dir(&dir)
+                .map_err(|source| BuildError::Load { path: dir, source }),
+            location => {
+                let tmpdir = create_tempdir()?;
+                let def_file = self.definition_files(tmpdir.path(), location)?.remove(0);
+                load(def_file, |path| load_github_linguist_languages(path))
+            }
         }
+    }
 
-        let target_path = self.out_path.clone();
-        let mut target_file = std::fs::File::create(target_path.join(name)).unwrap();
-        _ = target_file.write_all("use linguist::serde::StaticHeuristicRule;\n\npub static HEURISTICS: &[&StaticHeuristicRule] = &[\n".to_string().as_bytes());
-        for str in entries {
-            _ = target_file.write_all(format!("    {},\n", str).as_bytes());
+    /// Used internally to load the definitions of the given `location` with the given loader.
+    fn load_all<T>(
+        &self,
+        location: Location,
+        loader: impl Fn(&Path) -> Result<Vec<T>, LinguistError>,
+    ) -> Result<Vec<T>, BuildError> {
+        let tmpdir = create_tempdir()?;
+        let mut data = Vec::new();
+        for def_file in self.definition_files(tmpdir.path(), location)? {
+            data.extend(load(def_file, &loader)?);
         }
-        _ = target_file.write_all("];\n".to_string().as_bytes());
-        _ = target_file.flush();
+        Ok(data)
     }
 
-    /// Generate a `Vendor` definition and writes it to the `out_path`.
-    fn generate_vendors(&self, name: &str, location: Location) {
-        let tmpdir = tempdir().expect("failed to create a tempdir");
-        let def_file = match location {
-            Location::URL(url) => self.download_from_url(tmpdir.path(), &url).unwrap(),
-            Location::Path(path) => path,
-        };
+    /// Used internally to write the given generated `content` into the file `name` in the
+    /// `out_path`.
+    fn write_definition(&self, name: &str, content: String) -> Result<(), BuildError> {
+        let path = self.out_path.join(name);
+        std::fs::write(&path, content).map_err(|source| BuildError::Io { path, source })
+    }
 
-        let data = load_github_vendors(def_file).unwrap();
+    /// Generates the content of a [`Language`] definition.
+    fn generate_language(&self, location: Location) -> Result<String, BuildError> {
+        let mut data = self.load_languages(location)?;
+        data.sort_by(|a, b| a.name.cmp(&b.name));
 
-        let target_path = self.out_path.clone();
-        let mut target_file = std::fs::File::create(target_path.join(name)).unwrap();
-        _ = target_file
-            .write_all(format!("pub static VENDORS: &[&str; {}] = &[", data.len()).as_bytes());
-        for str in data {
-            _ = target_file.write_all(format!("    r\"{}\",\n", str).as_bytes());
+        let mut content = "use linguist::serde::StaticLanguage;\n\npub static LANGUAGES: &[&StaticLanguage] = &[\n".to_string();
+        for item in data.iter() {
+            content.push_str(&format!("    {},\n", write_language_definition(item)));
         }
+        content.push_str("];\n");
+        Ok(content)
+    }
+
+    /// Generates the content of a [`HeuristicRule`] definition.
+    fn generate_heuristics(&self, location: Location) -> Result<String, BuildError> {
+        let data = self.load_all(location, |path| load_github_linguist_heuristics(path))?;
 
-        _ = target_file.write_all("];\n".to_string().as_bytes());
-        _ = target_file.flush();
+        let mut content = "use linguist::serde::StaticHeuristicRule;\n\npub static HEURISTICS: &[&StaticHeuristicRule] = &[\n".to_string();
+        for item in data.iter() {
+            content.push_str(&format!("    {},\n", write_heuristic_definition(item)));
+        }
+        content.push_str("];\n");
+        Ok(content)
     }
 
-    /// Generate a `Documentation` definition and writes it to the `out_path`.
-    fn generate_documentation(&self, name: &str, location: Location) {
-        let tmpdir = tempdir().expect("failed to create a tempdir");
-        let def_file = match location {
-            Location::URL(url) => self.download_from_url(tmpdir.path(), &url).unwrap(),
-            Location::Path(path) => path,
-        };
+    /// Generates the content of a `Vendor` definition.
+    fn generate_vendors(&self, location: Location) -> Result<String, BuildError> {
+        let data = self.load_all(location, |path| load_github_vendors(path))?;
 
-        let data = load_github_documentation(def_file).unwrap();
+        let mut content = format!("pub static VENDORS: &[&str; {}] = &[", data.len());
+        for str in data {
+            content.push_str(&format!("    {},\n", literal(&str)));
+        }
+        content.push_str("];\n");
+        Ok(content)
+    }
 
-        let target_path = self.out_path.clone();
-        let mut target_file = std::fs::File::create(target_path.join(name)).unwrap();
-        _ = target_file.write_all(
-            format!("pub static DOCUMENTATION: &[&str; {}] = &[", data.len()).as_bytes(),
-        );
+    /// Generates the content of a `Documentation` definition.
+    fn generate_documentation(&self, location: Location) -> Result<String, BuildError> {
+        let data = self.load_all(location, |path| load_github_documentation(path))?;
+
+        let mut content = format!("pub static DOCUMENTATION: &[&str; {}] = &[", data.len());
         for str in data {
-            _ = target_file.write_all(format!("    r\"{}\",\n", str).as_bytes());
+            content.push_str(&format!("    {},\n", literal(&str)));
         }
+        content.push_str("];\n");
+        Ok(content)
+    }
 
-        _ = target_file.write_all("];\n".to_string().as_bytes());
-        _ = target_file.flush();
+    /// Generates the content of a `LanguageUrls` definition.
+    fn generate_language_urls(&self, location: Location) -> Result<String, BuildError> {
+        let data: BTreeMap<String, String> = self
+            .load_all(location, |path| load_language_urls(path))?
+            .into_iter()
+            .collect();
+
+        let mut content = format!(
+            "pub static LANGUAGE_URLS: &[(&str, &str); {}] = &[\n",
+            data.len()
+        );
+        for (lang, url) in data {
+            content.push_str(&format!("    ({}, {}),\n", literal(&lang), literal(&url)));
+        }
+        content.push_str("];\n");
+        Ok(content)
     }
 
-    /// Generates all configured definitions and writes them to the `out_path`.
-    pub fn generate(&self) {
+    /// Generates all configured definitions and writes them to the `out_path`. Generation stops at
+    /// the first definition that fails, e.g., because it cannot be downloaded, so a build script
+    /// can decide whether to fail or to fall back to a cached copy.
+    ///
+    /// The generated files are reproducible: languages are written ordered by name, heuristic
+    /// rules in the order of their definition, as it decides between rules that match equally
+    /// well. Repeated builds therefore produce byte-identical files.
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::PathBuf;
+    ///
+    /// use linguist_build::{BuildError, Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// let result = Config::new()
+    ///     .add_definition(Definition {
+    ///         name: "vendors.rs".to_string(),
+    ///         location: Location::Path(PathBuf::from("../linguist/tests/fixtures/github/missing.yml")),
+    ///         kind: Kind::Vendors,
+    ///     })
+    ///     .generate();
+    ///
+    /// match result {
+    ///     Err(BuildError::Load { path, .. }) => assert!(path.ends_with("missing.yml")),
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    ///
+    /// All strings are written as escaped literals, so definitions containing quotes or
+    /// backslashes still generate valid code:
+    /// ```
+    /// use linguist_build::{verify_generated, Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// let languages = out_dir.path().join("languages.yml");
+    /// std::fs::write(
+    ///     &languages,
+    ///     "Quoted:\n  type: programming\n  aliases:\n  - 'say \"hi\"'\n  - 'back\\slash'\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let definition = Definition {
+    ///     name: "languages.rs".to_string(),
+    ///     location: Location::Path(languages),
+    ///     kind: Kind::Languages,
+    /// };
+    /// Config::new().add_definition(definition.clone()).generate().unwrap();
+    ///
+    /// let generated = out_dir.path().join("languages.rs");
+    /// let content = std::fs::read_to_string(&generated).unwrap();
+    /// assert!(content.contains(r#"aliases: Some(&["say \"hi\"", "back\\slash"])"#));
+    /// verify_generated(&generated, &definition).unwrap();
+    /// ```
+    ///
+    /// Languages are ordered by name, independent of the order in the definition:
+    /// ```
+    /// use linguist_build::{Config, Definition, Kind, Location};
+    ///
+    /// let out_dir = tempfile::tempdir().unwrap();
+    /// std::env::set_var("OUT_DIR", out_dir.path());
+    ///
+    /// let languages = out_dir.path().join("languages.yml");
+    /// std::fs::write(&languages, "Zig:\n  type: programming\nAda:\n  type: programming\n").unwrap();
+    ///
+    /// let mut config = Config::new();
+    /// config.add_definition(Definition {
+    ///     name: "languages.rs".to_string(),
+    ///     location: Location::Path(languages),
+    ///     kind: Kind::Languages,
+    /// });
+    ///
+    /// config.generate().unwrap();
+    /// let first = std::fs::read_to_string(out_dir.path().join("languages.rs")).unwrap();
+    /// config.generate().unwrap();
+    /// let second = std::fs::read_to_string(out_dir.path().join("languages.rs")).unwrap();
+    ///
+    /// assert_eq!(first, second);
+    /// assert!(first.find("\"Ada\"").unwrap() < first.find("\"Zig\"").unwrap());
+    /// ```
+    pub fn generate(&self) -> Result<(), BuildError> {
+        let mut combined = String::new();
+        let mut registrations = String::new();
         for def in self.definitions.iter() {
+            // in the combined output, every definition lives in its own module
+            let prefix = match self.combined_output {
+                Some(_) => format!("{}::", module_name(&def.name)),
+                None => String::new(),
+            };
             match def.kind {
-                Kind::Languages => self.generate_language(&def.name, def.location.clone()),
-                Kind::Heuristics => self.generate_heuristics(&def.name, def.location.clone()),
-                Kind::Vendors => self.generate_vendors(&def.name, def.location.clone()),
-                Kind::Documentation => self.generate_documentation(&def.name, def.location.clone()),
+                Kind::Languages => registrations.push_str(&format!(
+                    "        .languages({}LANGUAGES.iter().copied())\n",
+                    prefix
+                )),
+                Kind::Heuristics => registrations.push_str(&format!(
+                    "        .heuristic_rules({}HEURISTICS.iter().copied())\n",
+                    prefix
+                )),
+                _ => {}
+            }
+
+            let location = def.location.clone();
+            let content = match def.kind {
+                Kind::Languages => self.generate_language(location)?,
+                Kind::Heuristics => self.generate_heuristics(location)?,
+                Kind::Vendors => self.generate_vendors(location)?,
+                Kind::Documentation => self.generate_documentation(location)?,
+                Kind::LanguageUrls => self.generate_language_urls(location)?,
             };
+            if self.combined_output.is_some() {
+                combined.push_str(&format!(
+                    "pub mod {} {{\n{}}}\n\n",
+                    module_name(&def.name),
+                    content
+                ));
+            } else {
+                self.write_definition(&def.name, content)?;
+            }
+        }
+        if let Some(name) = &self.container_output {
+            let content = format!(
+                "pub fn build_container() -> linguist::container::InMemoryLanguageContainer {{\n    linguist::container::ContainerBuilder::new()\n{}        .build()\n}}\n",
+                registrations
+            );
+            if self.combined_output.is_some() {
+                combined.push_str(&content);
+            } else {
+                self.write_definition(name, content)?;
+            }
+        }
+        match &self.combined_output {
+            Some(name) => self.write_definition(name, combined),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Used internally to derive the module name of a definition in the combined output from its
+/// file `name`, e.g., `language-urls.rs` becomes `language_urls`.
+fn module_name(name: &str) -> String {
+    let stem = Path::new(name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut module: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !module.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        module.insert(0, '_');
+    }
+    module
+}
+
+/// Used internally to check whether a failed download may succeed when it is retried.
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err.status().is_some_and(|status| {
+            status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        })
+}
+
+/// Used internally to name the file that the definition of the given `url` is downloaded to, so
+/// downloads of different URLs never share a file.
+fn download_file_name(url: &str) -> String {
+    // FNV-1a, which, unlike the std hasher, is stable across Rust releases
+    let hash = url.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}.yml", hash)
+}
+
+/// Used internally to create a tempdir for downloaded definitions.
+fn create_tempdir() -> Result<TempDir, BuildError> {
+    tempdir().map_err(|source| BuildError::Io {
+        path: std::env::temp_dir(),
+        source,
+    })
+}
+
+/// Used internally to load the definitions of the given file with the given loader.
+fn load<T>(
+    path: PathBuf,
+    loader: impl Fn(&Path) -> Result<Vec<T>, LinguistError>,
+) -> Result<Vec<T>, BuildError> {
+    loader(&path).map_err(|source| BuildError::Load { path, source })
+}
+
+/// A `BuildError` describes why a [`Definition`] could not be generated. It carries the URL or the
+/// path of the failing definition and the underlying error.
+#[derive(Debug)]
+pub enum BuildError {
+    /// The definition could not be downloaded from the given URL. The `status` is set if the
+    /// server responded with an error status.
+    Download {
+        url: String,
+        status: Option<reqwest::StatusCode>,
+        source: reqwest::Error,
+    },
+    /// The given file or directory could not be read or written.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The given definition file could not be loaded, e.g., because it is not valid YAML.
+    Load {
+        path: PathBuf,
+        source: LinguistError,
+    },
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::Download {
+                url,
+                status: Some(status),
+                source,
+            } => write!(f, "cannot download {} ({}): {}", url, status, source),
+            BuildError::Download { url, source, .. } => {
+                write!(f, "cannot download {}: {}", url, source)
+            }
+            BuildError::Io { path, source } => {
+                write!(f, "cannot access {}: {}", path.display(), source)
+            }
+            BuildError::Load { path, source } => {
+                write!(f, "cannot load {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Download { source, .. } => Some(source),
+            BuildError::Io { source, .. } => Some(source),
+            BuildError::Load { source, .. } => Some(source),
         }
     }
 }
@@ -214,21 +815,231 @@ impl std::fmt::Debug for Config {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Config")
             .field("definitions", &self.definitions)
+            .field("combined_output", &self.combined_output)
+            .field("container_output", &self.container_output)
+            .field("http_timeout", &self.http_timeout)
+            .field("retries", &self.retries)
+            // the values are left out, as they may contain credentials
+            .field(
+                "headers",
+                &self
+                    .headers
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
             .finish()
     }
 }
 
+/// A `Diff` describes how a generated languages file differs from its [`Definition`], see
+/// [`verify_generated`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diff {
+    /// The language is defined, but missing from the generated file.
+    Added(String),
+    /// The language is part of the generated file, but no longer defined.
+    Removed(String),
+    /// The language is defined differently than in the generated file.
+    Changed(String),
+}
+
+impl std::fmt::Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diff::Added(name) => write!(f, "added: {}", name),
+            Diff::Removed(name) => write!(f, "removed: {}", name),
+            Diff::Changed(name) => write!(f, "changed: {}", name),
+        }
+    }
+}
+
+/// A `VerifyError` describes why a generated file does not correspond to its [`Definition`], see
+/// [`verify_generated`].
+#[derive(Debug)]
+pub enum VerifyError {
+    /// Only definitions of [`Kind::Languages`] can be verified.
+    UnsupportedKind(Kind),
+    /// The definition or the generated file could not be read.
+    Build(BuildError),
+    /// The generated file differs from the definition; the differences are ordered by language
+    /// name.
+    Outdated(Vec<Diff>),
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::UnsupportedKind(kind) => {
+                write!(f, "cannot verify definitions of kind {:?}", kind)
+            }
+            VerifyError::Build(err) => err.fmt(f),
+            VerifyError::Outdated(diffs) => {
+                let diffs: Vec<String> = diffs.iter().map(Diff::to_string).collect();
+                write!(f, "generated file is outdated ({})", diffs.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VerifyError::Build(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<BuildError> for VerifyError {
+    fn from(err: BuildError) -> Self {
+        VerifyError::Build(err)
+    }
+}
+
+/// Verifies that a generated languages file, e.g., a vendored `languages.rs`, still corresponds to
+/// the given [`Kind::Languages`] definition. The definition is generated in memory and compared
+/// against the file language by language; the differences are ordered by language name. A
+/// definition of another kind, or a definition or generated file that cannot be read, is reported
+/// as a [`VerifyError`] as well.
+///
+/// # Example
+/// ```
+/// use std::path::PathBuf;
+///
+/// use linguist_build::{
+///     verify_generated, BuildError, Config, Definition, Diff, Kind, Location, VerifyError,
+/// };
+///
+/// let out_dir = tempfile::tempdir().unwrap();
+/// std::env::set_var("OUT_DIR", out_dir.path());
+///
+/// let definition = Definition {
+///     name: "languages.rs".to_string(),
+///     location: Location::Path(PathBuf::from("../linguist/tests/fixtures/github/languages.yml")),
+///     kind: Kind::Languages,
+/// };
+/// Config::new().add_definition(definition.clone()).generate().unwrap();
+///
+/// let generated = out_dir.path().join("languages.rs");
+/// verify_generated(&generated, &definition).unwrap();
+///
+/// let content = std::fs::read_to_string(&generated).unwrap();
+/// let content: Vec<&str> = content
+///     .lines()
+///     .filter(|line| !line.contains("name: \"Rust\""))
+///     .collect();
+/// std::fs::write(&generated, content.join("\n").replace("#555555", "#000000")).unwrap();
+///
+/// match verify_generated(&generated, &definition) {
+///     Err(VerifyError::Outdated(diffs)) => assert_eq!(
+///         diffs,
+///         [Diff::Changed("C".to_string()), Diff::Added("Rust".to_string())]
+///     ),
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+///
+/// // names are read with the escape rules they are written with, and formatting is ignored
+/// let names = out_dir.path().join("names.yml");
+/// std::fs::write(&names, "'Say \"hi\"':\n  type: programming\n  color: '#123456'\n").unwrap();
+/// let quoted = Definition {
+///     name: "names.rs".to_string(),
+///     location: Location::Path(names),
+///     kind: Kind::Languages,
+/// };
+/// Config::new().add_definition(quoted.clone()).generate().unwrap();
+/// let names = out_dir.path().join("names.rs");
+/// verify_generated(&names, &quoted).unwrap();
+///
+/// let content = std::fs::read_to_string(&names).unwrap();
+/// std::fs::write(&names, content.replace(", ", ",\n        ")).unwrap();
+/// verify_generated(&names, &quoted).unwrap();
+///
+/// std::fs::write(&names, content.replace("#123456", "#654321")).unwrap();
+/// match verify_generated(&names, &quoted) {
+///     Err(VerifyError::Outdated(diffs)) => {
+///         assert_eq!(diffs, [Diff::Changed("Say \"hi\"".to_string())])
+///     }
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+///
+/// std::fs::remove_file(&generated).unwrap();
+/// match verify_generated(&generated, &definition) {
+///     Err(VerifyError::Build(BuildError::Io { path, .. })) => assert_eq!(path, generated),
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+///
+/// let definition = Definition {
+///     kind: Kind::Vendors,
+///     ..definition
+/// };
+/// assert!(matches!(
+///     verify_generated(&generated, &definition),
+///     Err(VerifyError::UnsupportedKind(Kind::Vendors))
+/// ));
+/// ```
+pub fn verify_generated(
+    generated_path: impl AsRef<Path>,
+    definition: &Definition,
+) -> Result<(), VerifyError> {
+    if definition.kind != Kind::Languages {
+        return Err(VerifyError::UnsupportedKind(definition.kind.clone()));
+    }
+
+    let config = Config::with_out_path(PathBuf::new());
+    let expected: BTreeMap<String, String> = config
+        .load_languages(definition.location.clone())?
+        .iter()
+        .map(|lang| {
+            let definition = normalize_definition(&write_language_definition(lang));
+            (lang.name.clone(), definition)
+        })
+        .collect();
+
+    let generated_path = generated_path.as_ref();
+    let content = std::fs::read_to_string(generated_path).map_err(|source| BuildError::Io {
+        path: generated_path.to_path_buf(),
+        source,
+    })?;
+    let actual: BTreeMap<String, String> = static_language_definitions(&content)
+        .into_iter()
+        .filter_map(|definition| Some((definition_name(&definition)?, definition)))
+        .collect();
+
+    let mut names: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let diffs: Vec<Diff> = names
+        .into_iter()
+        .filter_map(|name| match (expected.get(name), actual.get(name)) {
+            (Some(_), None) => Some(Diff::Added(name.clone())),
+            (None, Some(_)) => Some(Diff::Removed(name.clone())),
+            (Some(expected), Some(actual)) if expected != actual => {
+                Some(Diff::Changed(name.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    if diffs.is_empty() {
+        Ok(())
+    } else {
+        Err(VerifyError::Outdated(diffs))
+    }
+}
+
 fn write_language_definition(lang: &Language) -> String {
     let mut str = String::new();
     str.push_str("&StaticLanguage {");
 
     if let Some(parent) = &lang.parent {
-        str.push_str(format!("parent: Some(\"{}\"), ", parent).as_str());
+        str.push_str(format!("parent: Some({}), ", literal(parent)).as_str());
     } else {
         str.push_str("parent: None, ");
     }
 
-    str.push_str(format!("name: \"{}\", ", &lang.name).as_str());
+    str.push_str(format!("name: {}, ", literal(&lang.name)).as_str());
 
     if !lang.aliases.is_empty() {
         str.push_str(
@@ -237,7 +1048,7 @@ fn write_language_definition(lang: &Language) -> String {
                 &lang
                     .aliases
                     .iter()
-                    .map(|s| format!("\"{}\"", s))
+                    .map(|s| literal(s))
                     .collect::<Vec<String>>()
                     .join(", ")
             )
@@ -248,7 +1059,7 @@ fn write_language_definition(lang: &Language) -> String {
     }
 
     // str.push_str(format!("scope: Scope::{}, ", &lang.scope.to_string()).as_str());
-    str.push_str(format!("scope: \"{}\", ", &lang.scope.to_string()).as_str());
+    str.push_str(format!("scope: {:?}, ", lang.scope.to_string()).as_str());
 
     if !lang.extensions.is_empty() {
         str.push_str(
@@ -257,7 +1068,7 @@ fn write_language_definition(lang: &Language) -> String {
                 &lang
                     .extensions
                     .iter()
-                    .map(|s| format!("\"{}\"", s.to_str().expect("cannot unwrap extension")))
+                    .map(|s| literal(s.to_str().expect("cannot unwrap extension")))
                     .collect::<Vec<String>>()
                     .join(", ")
             )
@@ -274,7 +1085,7 @@ fn write_language_definition(lang: &Language) -> String {
                 &lang
                     .filenames
                     .iter()
-                    .map(|s| format!("\"{}\"", s.to_str().expect("cannot unwrap filename")))
+                    .map(|s| literal(s.to_str().expect("cannot unwrap filename")))
                     .collect::<Vec<String>>()
                     .join(", ")
             )
@@ -291,7 +1102,7 @@ fn write_language_definition(lang: &Language) -> String {
                 &lang
                     .interpreters
                     .iter()
-                    .map(|s| format!("\"{}\"", s))
+                    .map(|s| literal(s))
                     .collect::<Vec<String>>()
                     .join(", ")
             )
@@ -302,21 +1113,182 @@ fn write_language_definition(lang: &Language) -> String {
     }
 
     if let Some(color) = &lang.color {
-        str.push_str(format!("color: Some(\"{}\") ", color).as_str());
+        str.push_str(format!("color: Some({}), ", literal(color)).as_str());
     } else {
-        str.push_str("color: None ");
+        str.push_str("color: None, ");
     }
 
+    if let Some(id) = lang.language_id {
+        str.push_str(format!("language_id: Some({}), ", id).as_str());
+    } else {
+        str.push_str("language_id: None, ");
+    }
+
+    for (field, value) in [
+        ("tm_scope", &lang.tm_scope),
+        ("ace_mode", &lang.ace_mode),
+        ("codemirror_mode", &lang.codemirror_mode),
+    ] {
+        if let Some(value) = value {
+            str.push_str(format!("{}: Some({:?}), ", field, value).as_str());
+        } else {
+            str.push_str(format!("{}: None, ", field).as_str());
+        }
+    }
+
+    str.push_str(format!("popular: {} ", lang.popular).as_str());
+
     str.push('}');
     str
 }
 
+/// Used internally to write the given `value` as a Rust string literal, escaping quotes,
+/// backslashes, and control characters.
+fn literal(value: &str) -> String {
+    format!("{:?}", value)
+}
+
+/// Used internally to split the string literal at the beginning of `code` from the code that
+/// follows it. Escaped quotes, see [`literal`], do not end the literal.
+fn split_literal(code: &str) -> Option<(&str, &str)> {
+    let bytes = code.as_bytes();
+    if bytes.first() != Some(&b'"') {
+        return None;
+    }
+
+    let mut index = 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' => return Some(code.split_at(index + 1)),
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+/// Used internally to read the value of a string literal that is written by [`literal`].
+fn parse_literal(literal: &str) -> Option<String> {
+    let mut value = String::new();
+    let mut chars = literal.strip_prefix('"')?.strip_suffix('"')?.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            'u' => {
+                let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+            escaped => value.push(escaped),
+        }
+    }
+    Some(value)
+}
+
+/// Used internally to normalize generated code, so that it can be compared independent of its
+/// formatting, e.g., by `rustfmt`: whitespace outside of string literals and trailing commas are
+/// removed.
+fn normalize_definition(code: &str) -> String {
+    let mut normalized = String::new();
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        if let Some((literal, tail)) = split_literal(rest) {
+            normalized.push_str(literal);
+            rest = tail;
+            continue;
+        }
+
+        if matches!(c, '}' | ']' | ')') && normalized.ends_with(',') {
+            normalized.pop();
+        }
+        if !c.is_whitespace() {
+            normalized.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    normalized
+}
+
+/// Used internally to extract the normalized `StaticLanguage` definitions of a generated
+/// languages file, see [`normalize_definition`].
+fn static_language_definitions(content: &str) -> Vec<String> {
+    const START: &str = "&StaticLanguage{";
+
+    let content = normalize_definition(content);
+    let mut definitions = Vec::new();
+    let mut rest = content.as_str();
+    while let Some(start) = rest.find(START) {
+        rest = &rest[start..];
+
+        // the definition ends at the matching brace; braces within literals are skipped
+        let mut depth = 0;
+        let mut index = 0;
+        let mut end = None;
+        while index < rest.len() {
+            if let Some((literal, _)) = split_literal(&rest[index..]) {
+                index += literal.len();
+                continue;
+            }
+            match rest.as_bytes()[index] {
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(index + 1);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            index += rest[index..].chars().next().map_or(1, char::len_utf8);
+        }
+
+        let end = end.unwrap_or(rest.len());
+        definitions.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    definitions
+}
+
+/// Used internally to read the name of a normalized `StaticLanguage` definition, see
+/// [`static_language_definitions`].
+fn definition_name(definition: &str) -> Option<String> {
+    let mut depth = 0;
+    let mut index = 0;
+    while index < definition.len() {
+        let rest = &definition[index..];
+        if let Some((literal, _)) = split_literal(rest) {
+            index += literal.len();
+            continue;
+        }
+        let byte = rest.as_bytes()[0];
+        match byte {
+            b'{' | b'(' | b'[' => depth += 1,
+            b'}' | b')' | b']' => depth -= 1,
+            _ => {}
+        }
+        if depth == 1 && matches!(byte, b'{' | b',') {
+            if let Some(value) = rest[1..].strip_prefix("name:") {
+                return parse_literal(split_literal(value)?.0);
+            }
+        }
+        index += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    None
+}
+
 /// Convert a [`HeuristicRule`] into a string representation (as rust code).
 fn write_heuristic_definition(rule: &HeuristicRule) -> String {
     let mut str = String::new();
     str.push_str("&StaticHeuristicRule {");
 
-    str.push_str(format!("language: \"{}\", ", &rule.language).as_str());
+    str.push_str(format!("language: {}, ", literal(&rule.language)).as_str());
 
     if !rule.extensions.is_empty() {
         str.push_str(
@@ -325,7 +1297,7 @@ fn write_heuristic_definition(rule: &HeuristicRule) -> String {
                 &rule
                     .extensions
                     .iter()
-                    .map(|s| format!("\"{}\"", s.to_str().expect("cannot unwrap extension")))
+                    .map(|s| literal(s.to_str().expect("cannot unwrap extension")))
                     .collect::<Vec<String>>()
                     .join(", ")
             )
@@ -335,21 +1307,22 @@ fn write_heuristic_definition(rule: &HeuristicRule) -> String {
         str.push_str("extensions: &[], ");
     }
 
-    if !rule.patterns.is_empty() {
+    for (field, patterns) in [
+        ("patterns", &rule.patterns),
+        ("required_patterns", &rule.required_patterns),
+    ] {
         str.push_str(
             format!(
-                "patterns: &[{}], ",
-                &rule
-                    .patterns
+                "{}: &[{}], ",
+                field,
+                patterns
                     .iter()
-                    .map(|s| format!("\"{}\"", s.replace('\\', "\\\\").replace('\"', "\\\"")))
+                    .map(|s| literal(s))
                     .collect::<Vec<String>>()
                     .join(", ")
             )
             .as_str(),
         );
-    } else {
-        str.push_str("patterns: &[] ");
     }
 
     str.push('}');
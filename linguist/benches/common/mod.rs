@@ -0,0 +1,29 @@
+//! A minimal timing helper shared by the benchmarks. They are run with `cargo bench` and only
+//! need the standard library, i.e., they work offline and on stable Rust.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Runs `f` the given number of times after a warm-up run, prints the mean time per run, and
+/// returns it.
+pub fn bench<T>(name: &str, iterations: u32, mut f: impl FnMut() -> T) -> Duration {
+    black_box(f());
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    let mean = start.elapsed() / iterations;
+
+    println!("{:<48} {:>12.3?}", name, mean);
+    mean
+}
+
+/// Prints how many times faster `fast` is than `slow`.
+pub fn speedup(name: &str, slow: Duration, fast: Duration) {
+    println!(
+        "{:<48} {:>11.1}x",
+        name,
+        slow.as_secs_f64() / fast.as_secs_f64()
+    );
+}
@@ -0,0 +1,76 @@
+//! Measures the cost of the content heuristics on a directory of large files with an ambiguous
+//! extension, once limited to the default content window and once over the whole files, see
+//! `ResolveOptions::max_content_bytes`.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use linguist::container::InMemoryLanguageContainer;
+use linguist::resolver::{resolve_language_with, HeuristicRule, Language, ResolveOptions};
+
+use common::{bench, speedup};
+
+const FILES: usize = 20;
+const FILE_SIZE: usize = 4 * 1024 * 1024;
+
+fn main() {
+    let dir = std::env::temp_dir().join(format!("linguist-bench-window-{}", std::process::id()));
+    let files = write_files(&dir);
+
+    let container = InMemoryLanguageContainer::builder()
+        .languages(["C", "C++", "Objective-C"].map(|name| Language::new(name).extensions(["h"])))
+        .heuristic_rule(HeuristicRule::new(
+            "Objective-C".to_string(),
+            vec!["h".into()],
+            vec![r"^\s*@(?:interface|class|protocol|property|end)\b".to_string()],
+        ))
+        .heuristic_rule(HeuristicRule::new(
+            "C++".to_string(),
+            vec!["h".into()],
+            vec![
+                r"^\s*template\s*<".to_string(),
+                r"^\s*(?:(?:inline|static|constexpr)\s+)*\w+(?:::\w+)+\s*\(".to_string(),
+            ],
+        ))
+        .build();
+
+    let resolve_all = |options: &ResolveOptions| {
+        for file in files.iter() {
+            resolve_language_with(file, &container, options).unwrap();
+        }
+    };
+
+    println!("{} files of {} bytes each", FILES, FILE_SIZE);
+    let windowed = bench("default content window", 5, || {
+        resolve_all(&ResolveOptions::default())
+    });
+    let unbounded = bench("whole files", 5, || {
+        resolve_all(&ResolveOptions {
+            max_content_bytes: Some(usize::MAX),
+            ..Default::default()
+        })
+    });
+    speedup("speedup of the content window", unbounded, windowed);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Writes large C++ headers whose distinguishing template is at the very beginning, followed by
+/// lots of declarations that the patterns have to scan.
+fn write_files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::create_dir_all(dir).unwrap();
+
+    let mut content = String::from("template <typename T>\nstruct Vector;\n");
+    while content.len() < FILE_SIZE {
+        content.push_str("extern int table_entry_with_a_long_name(int index, const char *key);\n");
+    }
+
+    (0..FILES)
+        .map(|i| {
+            let path = dir.join(format!("header{}.h", i));
+            std::fs::write(&path, &content).unwrap();
+            path
+        })
+        .collect()
+}
@@ -0,0 +1,82 @@
+//! Measures the speedup of `ResolveOptions::short_circuit_on_filename` on a tree of lockfiles and
+//! manifests, which are all identified by their exact filename.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use linguist::container::InMemoryLanguageContainer;
+use linguist::resolver::{resolve_language_with, HeuristicRule, Language, ResolveOptions};
+
+use common::{bench, speedup};
+
+const PROJECTS: usize = 200;
+const MANIFESTS: [&str; 6] = [
+    "Cargo.lock",
+    "Cargo.toml",
+    "go.mod",
+    "package.json",
+    "Gemfile.lock",
+    "poetry.lock",
+];
+
+fn main() {
+    let dir = std::env::temp_dir().join(format!("linguist-bench-manifests-{}", std::process::id()));
+    let files = write_files(&dir);
+
+    let container = InMemoryLanguageContainer::builder()
+        .languages([
+            Language::new("TOML")
+                .extensions(["toml", "lock"])
+                .filenames(["Cargo.lock", "Cargo.toml", "poetry.lock"]),
+            Language::new("Go Module").filenames(["go.mod"]),
+            Language::new("JSON")
+                .extensions(["json", "lock"])
+                .filenames(["package.json"]),
+            Language::new("Gemfile.lock").filenames(["Gemfile.lock"]),
+        ])
+        .heuristic_rule(HeuristicRule::new(
+            "JSON".to_string(),
+            vec!["lock".into()],
+            vec![r#"^\s*\{\s*"\w+"\s*:"#.to_string()],
+        ))
+        .build();
+
+    let resolve_all = |options: &ResolveOptions| {
+        for file in files.iter() {
+            resolve_language_with(file, &container, options).unwrap();
+        }
+    };
+
+    println!("{} manifests and lockfiles", files.len());
+    let full = bench("full vote", 10, || resolve_all(&ResolveOptions::default()));
+    let short = bench("short circuit on filename", 10, || {
+        resolve_all(&ResolveOptions {
+            short_circuit_on_filename: true,
+            ..Default::default()
+        })
+    });
+    speedup("speedup of the short circuit", full, short);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Writes a tree of projects, each with the typical manifests and lockfiles of a few kilobytes.
+fn write_files(dir: &Path) -> Vec<PathBuf> {
+    let mut content = String::new();
+    while content.len() < 32 * 1024 {
+        content.push_str("[[package]]\nname = \"dependency\"\nversion = \"1.0.0\"\n\n");
+    }
+
+    let mut files = Vec::new();
+    for project in 0..PROJECTS {
+        let project = dir.join(format!("project{}", project));
+        std::fs::create_dir_all(&project).unwrap();
+        for manifest in MANIFESTS {
+            let path = project.join(manifest);
+            std::fs::write(&path, &content).unwrap();
+            files.push(path);
+        }
+    }
+    files
+}
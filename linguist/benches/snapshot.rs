@@ -0,0 +1,61 @@
+//! Compares the startup cost of loading a container from the GitHub YAML definitions, from JSON,
+//! and from a binary snapshot, see `InMemoryLanguageContainer::to_bincode`.
+//!
+//! The full GitHub set is not part of the repository, so a set of the same size is generated.
+
+mod common;
+
+use linguist::container::InMemoryLanguageContainer;
+use linguist::github::load_github_linguist_languages_from_str;
+use linguist::resolver::Language;
+
+use common::{bench, speedup};
+
+/// The number of languages in GitHub's `languages.yml`, roughly.
+const LANGUAGES: usize = 700;
+
+fn main() {
+    let yaml = generate_languages_yml(LANGUAGES);
+    let languages = load_github_linguist_languages_from_str(&yaml).unwrap();
+    let json = serde_json::to_string(&languages).unwrap();
+    let snapshot = InMemoryLanguageContainer::from_languages(languages)
+        .to_bincode()
+        .unwrap();
+
+    println!(
+        "{} languages: {} bytes of YAML, {} bytes of JSON, {} bytes of bincode",
+        LANGUAGES,
+        yaml.len(),
+        json.len(),
+        snapshot.len()
+    );
+
+    let yaml_time = bench("load from YAML", 20, || {
+        InMemoryLanguageContainer::from_languages(
+            load_github_linguist_languages_from_str(&yaml).unwrap(),
+        )
+    });
+    let json_time = bench("load from JSON", 20, || {
+        InMemoryLanguageContainer::from_languages(
+            serde_json::from_str::<Vec<Language>>(&json).unwrap(),
+        )
+    });
+    let bincode_time = bench("load from bincode", 20, || {
+        InMemoryLanguageContainer::from_bincode(&snapshot).unwrap()
+    });
+
+    speedup("bincode speedup over YAML", yaml_time, bincode_time);
+    speedup("bincode speedup over JSON", json_time, bincode_time);
+}
+
+/// Generates a `languages.yml` in the format of GitHub's Linguist with the given number of
+/// languages, each with a typical number of aliases, extensions, filenames, and interpreters.
+fn generate_languages_yml(count: usize) -> String {
+    let mut yaml = String::new();
+    for i in 0..count {
+        yaml.push_str(&format!(
+            "Language {i}:\n  type: programming\n  color: \"#{i:06x}\"\n  aliases:\n  - lang{i}\n  - l{i}\n  extensions:\n  - \".l{i}\"\n  - \".lang{i}\"\n  - \".x{i}\"\n  filenames:\n  - Langfile{i}\n  interpreters:\n  - lang{i}\n  tm_scope: source.lang{i}\n  ace_mode: text\n  codemirror_mode: clike\n  language_id: {i}\n"
+        ));
+    }
+    yaml
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use linguist::utils::parse_shebang;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(info) = parse_shebang(data) {
+        assert!(!info.interpreter.is_empty());
+    }
+});
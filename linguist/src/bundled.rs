@@ -0,0 +1,491 @@
+//! A curated subset of GitHub's Linguist definitions that is compiled into the library, so
+//! languages can be detected without generating definitions with `linguist-build` first.
+//!
+//! The subset only covers widespread languages; use a [`Container`] populated from the full
+//! definitions for anything beyond quick scripts and tools.
+use std::{path::Path, sync::OnceLock};
+
+use crate::{
+    container::InMemoryLanguageContainer,
+    resolver::{resolve_language, resolve_language_from_bytes, Language},
+    serde::{StaticHeuristicRule, StaticLanguage},
+};
+
+/// The bundled [`Language`] definitions.
+pub static LANGUAGES: &[&StaticLanguage] = &[
+    &StaticLanguage {
+        name: "C",
+        scope: "programming",
+        aliases: None,
+        extensions: Some(&["c", "h"]),
+        filenames: None,
+        interpreters: Some(&["tcc"]),
+        color: Some("#555555"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "C#",
+        scope: "programming",
+        aliases: Some(&["csharp", "cake", "cakescript"]),
+        extensions: Some(&["cs", "csx"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#178600"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "C++",
+        scope: "programming",
+        aliases: Some(&["cpp"]),
+        extensions: Some(&["cpp", "cc", "cxx", "h", "hh", "hpp", "hxx"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#f34b7d"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "CSS",
+        scope: "markup",
+        aliases: None,
+        extensions: Some(&["css"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#563d7c"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "Dockerfile",
+        scope: "programming",
+        aliases: Some(&["containerfile"]),
+        extensions: Some(&["dockerfile"]),
+        filenames: Some(&["Containerfile", "Dockerfile"]),
+        interpreters: None,
+        color: Some("#384d54"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "Go",
+        scope: "programming",
+        aliases: Some(&["golang"]),
+        extensions: Some(&["go"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#00ADD8"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "HTML",
+        scope: "markup",
+        aliases: Some(&["xhtml"]),
+        extensions: Some(&["html", "htm", "xht", "xhtml"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#e34c26"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "JSON",
+        scope: "data",
+        aliases: Some(&["geojson", "jsonl", "topojson"]),
+        extensions: Some(&["json", "jsonl", "geojson", "topojson"]),
+        filenames: Some(&[
+            ".arcconfig",
+            ".watchmanconfig",
+            "composer.lock",
+            "flake.lock",
+        ]),
+        interpreters: None,
+        color: Some("#292929"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "Java",
+        scope: "programming",
+        aliases: None,
+        extensions: Some(&["java", "jav"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#b07219"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "JavaScript",
+        scope: "programming",
+        aliases: Some(&["js", "node"]),
+        extensions: Some(&["js", "cjs", "mjs", "jsx"]),
+        filenames: Some(&["Jakefile"]),
+        interpreters: Some(&["node", "nodejs", "qjs"]),
+        color: Some("#f1e05a"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "Kotlin",
+        scope: "programming",
+        aliases: None,
+        extensions: Some(&["kt", "kts", "ktm"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#A97BFF"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "Makefile",
+        scope: "programming",
+        aliases: Some(&["bsdmake", "make", "mf"]),
+        extensions: Some(&["mak", "mk", "mkfile"]),
+        filenames: Some(&["GNUmakefile", "Makefile", "makefile"]),
+        interpreters: Some(&["make"]),
+        color: Some("#427819"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "Markdown",
+        scope: "prose",
+        aliases: Some(&["md", "pandoc"]),
+        extensions: Some(&["md", "markdown", "mdown", "mkd"]),
+        filenames: Some(&["contents.lr"]),
+        interpreters: None,
+        color: Some("#083fa1"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "Objective-C",
+        scope: "programming",
+        aliases: Some(&["obj-c", "objc", "objectivec"]),
+        extensions: Some(&["m", "h"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#438eff"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "PHP",
+        scope: "programming",
+        aliases: Some(&["inc"]),
+        extensions: Some(&["php", "phtml", "php3", "php4", "php5", "phps"]),
+        filenames: Some(&[".php", ".php_cs", ".php_cs.dist"]),
+        interpreters: Some(&["php"]),
+        color: Some("#4F5D95"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "Python",
+        scope: "programming",
+        aliases: Some(&["python3", "rusthon"]),
+        extensions: Some(&["py", "pyi", "pyw", "gyp"]),
+        filenames: Some(&[
+            "BUILD",
+            "SConscript",
+            "SConstruct",
+            "Snakefile",
+            "WORKSPACE",
+        ]),
+        interpreters: Some(&["python", "python2", "python3", "py"]),
+        color: Some("#3572A5"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "Ruby",
+        scope: "programming",
+        aliases: Some(&["jruby", "macruby", "rake", "rb", "rbx"]),
+        extensions: Some(&["rb", "gemspec", "rake", "ru"]),
+        filenames: Some(&["Gemfile", "Rakefile", "Vagrantfile", "Podfile", "Brewfile"]),
+        interpreters: Some(&["ruby", "jruby", "rbx"]),
+        color: Some("#701516"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "Rust",
+        scope: "programming",
+        aliases: Some(&["rs"]),
+        extensions: Some(&["rs"]),
+        filenames: None,
+        interpreters: Some(&["rust-script"]),
+        color: Some("#dea584"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "SQL",
+        scope: "data",
+        aliases: None,
+        extensions: Some(&["sql"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#e38c00"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "Shell",
+        scope: "programming",
+        aliases: Some(&["sh", "shell-script", "bash", "zsh"]),
+        extensions: Some(&["sh", "bash", "zsh"]),
+        filenames: Some(&[".bashrc", ".bash_profile", ".profile", ".zshrc", "PKGBUILD"]),
+        interpreters: Some(&["ash", "bash", "dash", "ksh", "sh", "zsh"]),
+        color: Some("#89e051"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "Swift",
+        scope: "programming",
+        aliases: None,
+        extensions: Some(&["swift"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#F05138"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "TOML",
+        scope: "data",
+        aliases: None,
+        extensions: Some(&["toml"]),
+        filenames: Some(&["Cargo.lock", "Gopkg.lock", "Pipfile", "poetry.lock"]),
+        interpreters: None,
+        color: Some("#9c4221"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "TSX",
+        scope: "programming",
+        aliases: None,
+        extensions: Some(&["tsx"]),
+        filenames: None,
+        interpreters: None,
+        color: Some("#3178c6"),
+        parent: Some("TypeScript"),
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "Text",
+        scope: "prose",
+        aliases: Some(&["fundamental", "plain text"]),
+        extensions: Some(&["txt"]),
+        filenames: Some(&["COPYING", "LICENSE", "README"]),
+        interpreters: None,
+        color: None,
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+    &StaticLanguage {
+        name: "TypeScript",
+        scope: "programming",
+        aliases: Some(&["ts"]),
+        extensions: Some(&["ts", "cts", "mts"]),
+        filenames: None,
+        interpreters: Some(&["deno", "ts-node", "tsx"]),
+        color: Some("#3178c6"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: true,
+    },
+    &StaticLanguage {
+        name: "YAML",
+        scope: "data",
+        aliases: Some(&["yml"]),
+        extensions: Some(&["yml", "yaml"]),
+        filenames: Some(&[".clang-format", ".clang-tidy", ".gemrc", "yarn.lock"]),
+        interpreters: None,
+        color: Some("#cb171e"),
+        parent: None,
+        language_id: None,
+        tm_scope: None,
+        ace_mode: None,
+        codemirror_mode: None,
+        popular: false,
+    },
+];
+
+/// The bundled [`crate::resolver::HeuristicRule`] definitions.
+pub static HEURISTICS: &[&StaticHeuristicRule] = &[
+    &StaticHeuristicRule {
+        language: "Objective-C",
+        extensions: &["h"],
+        patterns: &[
+            r#"^\s*(@(interface|class|protocol|property|end|synchronised|selector|implementation)\b|#import\s+.+\.h[">])"#,
+        ],
+        required_patterns: &[],
+    },
+    &StaticHeuristicRule {
+        language: "C++",
+        extensions: &["h"],
+        patterns: &[
+            r"^\s*(#\s*include <(cstdint|string|vector|map|list|array|bitset|queue|stack|forward_list|unordered_map|unordered_set|(i|o|io)stream)>|template\s*<|[ \t]*try|catch\s*\(|(class|(using[ \t]+)?namespace)\s+\w+|[ \t]*(private|public|protected):$|std::\w+)",
+        ],
+        required_patterns: &[],
+    },
+];
+
+static CONTAINER: OnceLock<InMemoryLanguageContainer> = OnceLock::new();
+
+/// Returns the container holding the bundled definitions. It is built on the first call and
+/// shared afterwards.
+pub fn container() -> &'static InMemoryLanguageContainer {
+    CONTAINER.get_or_init(|| {
+        let mut container = InMemoryLanguageContainer::default();
+        for &lang in LANGUAGES.iter() {
+            container.register_language(lang);
+        }
+        for &rule in HEURISTICS.iter() {
+            container.register_heuristic_rule(rule);
+        }
+        container
+    })
+}
+
+/// Detects the [`Language`] of the given file using the bundled definitions, see
+/// [`crate::bundled`]. Returns `None` if the file cannot be read, is binary, or matches none of the
+/// bundled languages.
+///
+/// # Example
+/// ```
+/// let lang = linguist::detect_path("tests/fixtures/heuristics/vector.h");
+/// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("C++"));
+/// ```
+pub fn detect_path(file: impl AsRef<Path>) -> Option<&'static Language> {
+    resolve_language(file, container()).ok().flatten()
+}
+
+/// Detects the [`Language`] of a file with the given name and content using the bundled
+/// definitions, see [`crate::bundled`]. Like [`detect_path`], the shebang, modeline, and content
+/// are considered, see [`resolve_language_from_bytes`]; binary content resolves to `None`.
+///
+/// # Example
+/// ```
+/// let lang = linguist::detect_bytes("Cargo.lock", b"version = 3\n");
+/// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("TOML"));
+///
+/// let lang = linguist::detect_bytes("list.h", b"@interface List : NSObject\n@end\n");
+/// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("Objective-C"));
+///
+/// let lang = linguist::detect_bytes("list.h", b"struct list;\n");
+/// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("C"));
+///
+/// let lang = linguist::detect_bytes("run", b"#!/usr/bin/env python3\nprint('hi')\n");
+/// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("Python"));
+/// ```
+pub fn detect_bytes(name: impl AsRef<Path>, content: &[u8]) -> Option<&'static Language> {
+    resolve_language_from_bytes(&name.as_ref().to_string_lossy(), content, container())
+        .ok()
+        .flatten()
+}
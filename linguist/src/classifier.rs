@@ -0,0 +1,660 @@
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use regex::RegexSet;
+
+use crate::{
+    container::Container,
+    error::LinguistError,
+    gitattributes::GitAttributes,
+    resolver::{
+        check_binary, resolve_language_by_magic, resolve_text_language, short_circuit_language,
+        Language, ResolveOptions, Scope,
+    },
+    stats::LanguageBreakdown,
+    utils::{
+        is_configuration, is_documentation, is_dotfile, is_generated, is_lfs_pointer, is_test,
+        is_vendor, relative_path, FIRST_FEW_BYTES,
+    },
+};
+
+/// A `FilterReason` describes why a file is excluded from the language statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterReason {
+    Vendored,
+    Documentation,
+    Generated,
+    Test,
+    Configuration,
+    Dotfile,
+    LfsPointer,
+}
+
+impl Display for FilterReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterReason::Vendored => write!(f, "Vendored"),
+            FilterReason::Documentation => write!(f, "Documentation"),
+            FilterReason::Generated => write!(f, "Generated"),
+            FilterReason::Test => write!(f, "Test"),
+            FilterReason::Configuration => write!(f, "Configuration"),
+            FilterReason::Dotfile => write!(f, "Dotfile"),
+            FilterReason::LfsPointer => write!(f, "LFS pointer"),
+        }
+    }
+}
+
+/// The vendor rules that [`Filters`] apply by default, a minimal subset of GitHub's `vendor.yml`.
+pub static DEFAULT_VENDOR_RULES: [&str; 4] = [
+    r"(^|/)vendor/",
+    r"(^|/)node_modules/",
+    r"(^|/)bower_components/",
+    r"(^|/)third[-_]party/",
+];
+
+/// The documentation rules that [`Filters`] apply by default, a minimal subset of GitHub's
+/// `documentation.yml`.
+pub static DEFAULT_DOCUMENTATION_RULES: [&str; 2] = [r"^docs?/", r"^[Dd]ocumentation/"];
+
+/// `Filters` decide which files are excluded before their language is resolved. By default,
+/// vendored, documentation, generated, and test files as well as Git LFS pointers are excluded;
+/// vendored and documentation files are matched by the [`DEFAULT_VENDOR_RULES`] and the
+/// [`DEFAULT_DOCUMENTATION_RULES`], which can be replaced, e.g., by GitHub's full rule sets. Use
+/// [`Filters::none`] to include everything.
+///
+/// If [`GitAttributes`] are supplied, their `linguist-vendored`, `linguist-documentation`, and
+/// `linguist-generated` attributes take precedence over the respective rules.
+#[derive(Debug, Clone)]
+pub struct Filters {
+    vendors: Option<RegexSet>,
+    documentation: Option<RegexSet>,
+    attributes: Option<GitAttributes>,
+    generated: bool,
+    tests: bool,
+    configuration: bool,
+    dotfiles: bool,
+    lfs_pointers: bool,
+}
+
+impl Default for Filters {
+    fn default() -> Self {
+        static VENDORS: OnceLock<RegexSet> = OnceLock::new();
+        static DOCUMENTATION: OnceLock<RegexSet> = OnceLock::new();
+
+        let compile = |rules: &[&str]| RegexSet::new(rules).expect("the default rules are valid");
+        Filters {
+            vendors: Some(
+                VENDORS
+                    .get_or_init(|| compile(&DEFAULT_VENDOR_RULES))
+                    .clone(),
+            ),
+            documentation: Some(
+                DOCUMENTATION
+                    .get_or_init(|| compile(&DEFAULT_DOCUMENTATION_RULES))
+                    .clone(),
+            ),
+            attributes: None,
+            generated: true,
+            tests: true,
+            configuration: false,
+            dotfiles: false,
+            lfs_pointers: true,
+        }
+    }
+}
+
+impl Filters {
+    /// Creates new `Filters` with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates `Filters` that do not exclude any file.
+    pub fn none() -> Self {
+        Filters {
+            vendors: None,
+            documentation: None,
+            attributes: None,
+            generated: false,
+            tests: false,
+            configuration: false,
+            dotfiles: false,
+            lfs_pointers: false,
+        }
+    }
+
+    /// Excludes all files matching any of the given vendor rules, e.g., a
+    /// [`VendorMatcher`](crate::utils::VendorMatcher), instead of the [`DEFAULT_VENDOR_RULES`].
+    pub fn vendors(mut self, rules: impl Into<RegexSet>) -> Self {
+        self.vendors = Some(rules.into());
+        self
+    }
+
+    /// Excludes all files matching any of the given documentation rules, e.g., a
+    /// [`DocumentationMatcher`](crate::utils::DocumentationMatcher), instead of the
+    /// [`DEFAULT_DOCUMENTATION_RULES`].
+    pub fn documentation(mut self, rules: impl Into<RegexSet>) -> Self {
+        self.documentation = Some(rules.into());
+        self
+    }
+
+    /// Applies the `linguist-vendored` attribute of the given [`GitAttributes`]: marked paths are
+    /// excluded, and paths marked with `-linguist-vendored` or `linguist-vendored=false` are
+    /// included even if they match a vendor rule. The `linguist-documentation` and
+    /// `linguist-generated` attributes are applied in the same way.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs::File;
+    ///
+    /// use regex::RegexSet;
+    /// use linguist::classifier::{classify_directory, Filters};
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::gitattributes::parse_gitattributes;
+    /// use linguist::resolver::{Language, Scope};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for (name, ext) in [("Rust", "rs"), ("JavaScript", "js"), ("C", "c")] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         scope: Scope::Programming,
+    ///         extensions: vec![ext.into()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// let root = "tests/fixtures/attributes";
+    /// let filters = Filters::new().vendors(RegexSet::new([r"(^|/)vendor/"]).unwrap());
+    /// let breakdown = classify_directory(root, &container, &filters).unwrap();
+    /// assert_eq!(breakdown.usage("JavaScript"), None);
+    /// assert!(breakdown.usage("C").is_some());
+    ///
+    /// // vendor/ is un-vendored and third_party/ is vendored by the .gitattributes file
+    /// let file = File::open("tests/fixtures/attributes/.gitattributes").unwrap();
+    /// let filters = filters.attributes(parse_gitattributes(file).unwrap());
+    /// let breakdown = classify_directory(root, &container, &filters).unwrap();
+    /// assert!(breakdown.usage("JavaScript").is_some());
+    /// assert_eq!(breakdown.usage("C"), None);
+    /// ```
+    pub fn attributes(mut self, attributes: GitAttributes) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    /// Sets whether generated files are excluded.
+    pub fn generated(mut self, enabled: bool) -> Self {
+        self.generated = enabled;
+        self
+    }
+
+    /// Sets whether test files are excluded.
+    pub fn tests(mut self, enabled: bool) -> Self {
+        self.tests = enabled;
+        self
+    }
+
+    /// Sets whether configuration files are excluded.
+    pub fn configuration(mut self, enabled: bool) -> Self {
+        self.configuration = enabled;
+        self
+    }
+
+    /// Sets whether dotfiles are excluded.
+    pub fn dotfiles(mut self, enabled: bool) -> Self {
+        self.dotfiles = enabled;
+        self
+    }
+
+    /// Sets whether Git LFS pointer files are excluded, see [`is_lfs_pointer`]. Unlike the other
+    /// filters, this requires reading the beginning of each file, see [`Filters::check_file`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::classifier::{classify_directory, Filters};
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{Language, Scope};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for (name, ext) in [("Rust", "rs"), ("SVG", "svg")] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         scope: Scope::Markup,
+    ///         extensions: vec![ext.into()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// // assets/logo.svg is a pointer to a file stored in Git LFS
+    /// let root = "tests/fixtures/lfs";
+    /// let breakdown = classify_directory(root, &container, &Filters::new()).unwrap();
+    /// assert_eq!(breakdown.usage("SVG"), None);
+    ///
+    /// let filters = Filters::new().lfs_pointers(false);
+    /// let breakdown = classify_directory(root, &container, &filters).unwrap();
+    /// assert!(breakdown.usage("SVG").is_some());
+    /// ```
+    pub fn lfs_pointers(mut self, enabled: bool) -> Self {
+        self.lfs_pointers = enabled;
+        self
+    }
+
+    /// Checks whether the given file is excluded and returns the respective [`FilterReason`].
+    /// The path is expected to be relative to the root of the scanned directory.
+    pub fn check(&self, file: impl AsRef<Path>) -> Option<FilterReason> {
+        let file = file.as_ref();
+
+        let attributes = self.attributes.as_ref();
+
+        let vendored = attributes.and_then(|attributes| attributes.vendored(file));
+        match (vendored, &self.vendors) {
+            (Some(true), _) => return Some(FilterReason::Vendored),
+            (None, Some(rules)) if is_vendor(file, rules) => return Some(FilterReason::Vendored),
+            _ => {}
+        }
+
+        let documentation = attributes.and_then(|attributes| attributes.documentation(file));
+        match (documentation, &self.documentation) {
+            (Some(true), _) => return Some(FilterReason::Documentation),
+            (None, Some(rules)) if is_documentation(file, rules) => {
+                return Some(FilterReason::Documentation)
+            }
+            _ => {}
+        }
+
+        let generated = attributes.and_then(|attributes| attributes.generated(file));
+        match generated {
+            Some(true) => return Some(FilterReason::Generated),
+            None if self.generated && is_generated(file, None) => {
+                return Some(FilterReason::Generated)
+            }
+            _ => {}
+        }
+
+        if self.tests && is_test(file) {
+            return Some(FilterReason::Test);
+        }
+
+        if self.configuration && is_configuration(file) {
+            return Some(FilterReason::Configuration);
+        }
+
+        if self.dotfiles && is_dotfile(file) {
+            return Some(FilterReason::Dotfile);
+        }
+
+        None
+    }
+
+    /// Checks whether the given file is excluded by its content and returns the respective
+    /// [`FilterReason`], i.e., whether it is a Git LFS pointer or generated, see
+    /// [`is_generated`]. The file is only read if a content filter is enabled.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::classifier::{FilterReason, Filters};
+    ///
+    /// let filters = Filters::new();
+    /// let check = |file| filters.check_file(file).unwrap();
+    /// assert_eq!(check("tests/fixtures/generated/bundle.js"), Some(FilterReason::Generated));
+    /// assert_eq!(check("tests/fixtures/generated/parser.c"), Some(FilterReason::Generated));
+    /// assert_eq!(check("tests/fixtures/generated/main.js"), None);
+    ///
+    /// let filters = Filters::new().generated(false);
+    /// assert_eq!(filters.check_file("tests/fixtures/generated/bundle.js").unwrap(), None);
+    /// ```
+    pub fn check_file(
+        &self,
+        file: impl AsRef<Path>,
+    ) -> Result<Option<FilterReason>, LinguistError> {
+        self.check_content(file.as_ref(), file.as_ref())
+    }
+
+    /// Checks whether a file of the project at `root` is excluded by its content, like
+    /// [`Filters::check_file`]. The file is read from `file`, but the `linguist-generated`
+    /// attribute, see [`Filters::attributes`], is looked up by the path relative to `root`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs::File;
+    ///
+    /// use linguist::classifier::{FilterReason, Filters};
+    /// use linguist::gitattributes::parse_gitattributes;
+    ///
+    /// // dist/app.js is minified, but marked with `-linguist-generated`
+    /// let root = "tests/fixtures/ungenerated";
+    /// let file = "tests/fixtures/ungenerated/dist/app.js";
+    /// let filters = Filters::new();
+    /// assert_eq!(filters.check_file_rel(file, root).unwrap(), Some(FilterReason::Generated));
+    ///
+    /// let attributes = File::open("tests/fixtures/ungenerated/.gitattributes").unwrap();
+    /// let filters = filters.attributes(parse_gitattributes(attributes).unwrap());
+    /// assert_eq!(filters.check_file_rel(file, root).unwrap(), None);
+    /// ```
+    pub fn check_file_rel(
+        &self,
+        file: impl AsRef<Path>,
+        root: impl AsRef<Path>,
+    ) -> Result<Option<FilterReason>, LinguistError> {
+        let file = file.as_ref();
+        self.check_content(file, relative_path(file, root))
+    }
+
+    /// Used internally to check the content of `file`, whose path relative to the project root
+    /// is `relative`.
+    fn check_content(
+        &self,
+        file: &Path,
+        relative: &Path,
+    ) -> Result<Option<FilterReason>, LinguistError> {
+        if !self.lfs_pointers && !self.excludes_generated(relative) {
+            return Ok(None);
+        }
+
+        let mut buf = Vec::new();
+        std::fs::File::open(file)?
+            .take(FIRST_FEW_BYTES as u64)
+            .read_to_end(&mut buf)?;
+
+        Ok(self.check_head(relative, &buf))
+    }
+
+    /// Used internally to check whether a file is excluded by the given beginning of its content,
+    /// like [`Filters::check_file_rel`], if the content has already been read.
+    pub(crate) fn check_head(&self, relative: &Path, head: &[u8]) -> Option<FilterReason> {
+        let head = &head[..head.len().min(FIRST_FEW_BYTES)];
+
+        if self.lfs_pointers && is_lfs_pointer(head) {
+            return Some(FilterReason::LfsPointer);
+        }
+
+        if self.excludes_generated(relative) && is_generated(relative, Some(head)) {
+            return Some(FilterReason::Generated);
+        }
+
+        None
+    }
+
+    /// Used internally to check whether the given file is excluded if its content is generated,
+    /// i.e., unless its `linguist-generated` attribute is unset.
+    fn excludes_generated(&self, relative: &Path) -> bool {
+        self.generated
+            && self
+                .attributes
+                .as_ref()
+                .and_then(|attributes| attributes.generated(relative))
+                != Some(false)
+    }
+}
+
+/// A `FileClassification` describes how a single file is treated by [`classify_file`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileClassification<'a> {
+    /// The file resolves to the contained language, which is counted in the language statistics.
+    Language(&'a Language),
+    /// The file resolves to the contained language, but its scope is not counted, e.g., `Data`.
+    ExcludedScope(&'a Language),
+    /// The file is excluded by the [`Filters`] for the contained reason.
+    Filtered(FilterReason),
+    /// The file is binary and its format is not detected.
+    Binary,
+    /// The language of the file cannot be resolved, or several languages are equally likely.
+    Unknown,
+}
+
+/// Classifies a single file of the project at `root`, like [`classify_directory`] does for each
+/// file. The steps are applied in order, and each step is only performed if the previous one does
+/// not decide the classification:
+///
+/// 1. the [`Filters`] are checked against the path relative to `root`, and then against the
+///    content of the file, e.g., for Git LFS pointers,
+/// 2. a unique filename or extension match is taken if enabled, see
+///    [`ResolveOptions::short_circuit_on_filename`],
+/// 3. binary files are detected, see [`ResolveOptions::detect_binary_formats`],
+/// 4. the language is resolved, see [`resolve_language_rel`](crate::resolver::resolve_language_rel),
+/// 5. only languages of the scopes in [`ResolveOptions::scopes`] are counted.
+///
+/// # Example
+/// ```
+/// use regex::RegexSet;
+/// use linguist::classifier::{classify_file, FileClassification, FilterReason, Filters};
+/// use linguist::container::{Container, InMemoryLanguageContainer};
+/// use linguist::gitattributes::parse_gitattributes;
+/// use linguist::resolver::{Language, ResolveOptions, Scope};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Rust".to_string(),
+///     scope: Scope::Programming,
+///     extensions: vec!["rs".into()],
+///     ..Default::default()
+/// });
+/// container.register_language(Language {
+///     name: "JavaScript".to_string(),
+///     scope: Scope::Programming,
+///     extensions: vec!["js".into()],
+///     ..Default::default()
+/// });
+///
+/// let root = "tests/fixtures/vendored-js";
+/// let filters = Filters::new().vendors(RegexSet::new([r"(^|/)vendor/"]).unwrap());
+/// let options = ResolveOptions::default();
+/// let classify = |file| classify_file(file, root, &container, &filters, &options).unwrap();
+///
+/// let rust = container.get_language_by_name("Rust").unwrap();
+/// assert_eq!(
+///     classify("tests/fixtures/vendored-js/src/main.rs"),
+///     FileClassification::Language(rust)
+/// );
+/// assert_eq!(
+///     classify("tests/fixtures/vendored-js/vendor/tiny-dom.js"),
+///     FileClassification::Filtered(FilterReason::Vendored)
+/// );
+///
+/// let root = "tests/fixtures/binary";
+/// let classify = |file| classify_file(file, root, &container, &filters, &options).unwrap();
+/// assert_eq!(classify("tests/fixtures/binary/records.dat"), FileClassification::Binary);
+///
+/// // the minified dist/app.js is marked with `-linguist-generated`
+/// let attributes = std::fs::File::open("tests/fixtures/ungenerated/.gitattributes").unwrap();
+/// let filters = filters.attributes(parse_gitattributes(attributes).unwrap());
+/// let root = std::fs::canonicalize("tests/fixtures/ungenerated").unwrap();
+/// let javascript = container.get_language_by_name("JavaScript").unwrap();
+/// assert_eq!(
+///     classify_file(root.join("dist/app.js"), &root, &container, &filters, &options).unwrap(),
+///     FileClassification::Language(javascript)
+/// );
+/// ```
+pub fn classify_file<'a>(
+    file: impl AsRef<Path>,
+    root: impl AsRef<Path>,
+    container: &'a impl Container,
+    filters: &Filters,
+    options: &ResolveOptions,
+) -> Result<FileClassification<'a>, LinguistError> {
+    let file = file.as_ref();
+    let relative = relative_path(file, root);
+
+    if let Some(reason) = filters.check(relative) {
+        return Ok(FileClassification::Filtered(reason));
+    }
+
+    if let Some(reason) = filters.check_content(file, relative)? {
+        return Ok(FileClassification::Filtered(reason));
+    }
+
+    let language = match short_circuit_language(relative, container, options) {
+        Some(lang) => lang,
+        _ if check_binary(file, options)? => {
+            if !options.detect_binary_formats {
+                return Ok(FileClassification::Binary);
+            }
+            match resolve_language_by_magic(file, container)? {
+                Some(lang) => lang,
+                _ => return Ok(FileClassification::Binary),
+            }
+        }
+        _ => match resolve_text_language(file, relative, container, options) {
+            Ok(Some(lang)) => lang,
+            Ok(None) | Err(LinguistError::LanguageNotFound) => {
+                return Ok(FileClassification::Unknown)
+            }
+            Err(err) => return Err(err),
+        },
+    };
+
+    if let Some(scopes) = &options.scopes {
+        if !scopes.contains(&language.scope) {
+            return Ok(FileClassification::ExcludedScope(language));
+        }
+    }
+
+    Ok(FileClassification::Language(language))
+}
+
+/// Resolves the languages of all files within the given directory and weights them by their size.
+/// Each file is classified by [`classify_file`], i.e., files are run through the given [`Filters`]
+/// first, and only `Programming` and `Markup` languages are counted, like in GitHub's Linguist.
+pub fn classify_directory(
+    root: impl AsRef<Path>,
+    container: &impl Container,
+    filters: &Filters,
+) -> Result<LanguageBreakdown, LinguistError> {
+    let options = ResolveOptions {
+        scopes: Some(HashSet::from([Scope::Programming, Scope::Markup])),
+        ..Default::default()
+    };
+    classify_directory_with(root, container, filters, &options)
+}
+
+/// Resolves the languages of all files within the given directory and weights them by their size,
+/// like [`classify_directory`], but uses the given [`ResolveOptions`], e.g., to decide which
+/// scopes are counted.
+///
+/// # Example
+/// ```
+/// use std::collections::HashSet;
+///
+/// use linguist::classifier::{classify_directory_with, Filters};
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{Language, ResolveOptions, Scope};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Rust".to_string(),
+///     scope: Scope::Programming,
+///     extensions: vec!["rs".into()],
+///     ..Default::default()
+/// });
+/// container.register_language(Language {
+///     name: "Markdown".to_string(),
+///     scope: Scope::Prose,
+///     extensions: vec!["md".into()],
+///     ..Default::default()
+/// });
+///
+/// let root = "tests/fixtures/scopes";
+/// let options = ResolveOptions {
+///     scopes: Some(HashSet::from([Scope::Programming])),
+///     ..Default::default()
+/// };
+/// let breakdown = classify_directory_with(root, &container, &Filters::new(), &options).unwrap();
+/// assert!(breakdown.usage("Rust").is_some());
+/// assert_eq!(breakdown.usage("Markdown"), None);
+///
+/// let options = ResolveOptions::default();
+/// let breakdown = classify_directory_with(root, &container, &Filters::new(), &options).unwrap();
+/// assert!(breakdown.usage("Markdown").is_some());
+/// ```
+pub fn classify_directory_with(
+    root: impl AsRef<Path>,
+    container: &impl Container,
+    filters: &Filters,
+    options: &ResolveOptions,
+) -> Result<LanguageBreakdown, LinguistError> {
+    let root = root.as_ref();
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    walk_directory(root, &mut files)?;
+
+    let mut breakdown = LanguageBreakdown::new();
+    for file in files {
+        match classify_file(&file, root, container, filters, options)? {
+            FileClassification::Language(language) => {
+                breakdown.add_usage(&language.name, std::fs::metadata(&file)?.len());
+            }
+            FileClassification::Filtered(reason) if options.track_filtered => {
+                breakdown.add_filtered(reason, std::fs::metadata(&file)?.len());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(breakdown)
+}
+
+/// Guesses the primary [`Language`] of the given directory, i.e., the language with the most bytes
+/// after all files have been run through the given [`Filters`].
+///
+/// # Example
+/// ```
+/// use linguist::classifier::{guess_primary_language, Filters};
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{Language, Scope};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Rust".to_string(),
+///     scope: Scope::Programming,
+///     extensions: vec!["rs".into()],
+///     ..Default::default()
+/// });
+/// container.register_language(Language {
+///     name: "JavaScript".to_string(),
+///     scope: Scope::Programming,
+///     extensions: vec!["js".into()],
+///     ..Default::default()
+/// });
+///
+/// // vendor/tiny-dom.js is larger than src/main.rs, but vendored
+/// let root = "tests/fixtures/vendored-js";
+/// let primary = guess_primary_language(root, &container, &Filters::new()).unwrap();
+/// assert_eq!(primary.map(|lang| lang.name.as_str()), Some("Rust"));
+///
+/// let primary = guess_primary_language(root, &container, &Filters::none()).unwrap();
+/// assert_eq!(primary.map(|lang| lang.name.as_str()), Some("JavaScript"));
+/// ```
+pub fn guess_primary_language<'a>(
+    root: impl AsRef<Path>,
+    container: &'a impl Container,
+    filters: &Filters,
+) -> Result<Option<&'a Language>, LinguistError> {
+    let breakdown = classify_directory(root, container, filters)?;
+    Ok(breakdown
+        .primary()
+        .and_then(|name| container.get_language_by_name(name)))
+}
+
+/// Used internally to collect all files within the given directory in a stable order. The `.git`
+/// directory is skipped.
+fn walk_directory(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), LinguistError> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if entry.file_name() != ".git" {
+                walk_directory(&entry.path(), files)?;
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+
+    Ok(())
+}
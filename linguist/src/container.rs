@@ -1,75 +1,1271 @@
-use std::{collections::HashMap, ffi::OsString, path::Path};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    ffi::{OsStr, OsString},
+    fmt::Display,
+    path::Path,
+};
 
-use crate::resolver::{HeuristicRule, Language};
+#[cfg(feature = "bincode")]
+use std::collections::BTreeMap;
+
+#[cfg(feature = "bincode")]
+use crate::error::LinguistError;
+use crate::{
+    resolver::{HeuristicRule, Language, Scope},
+    utils::{detect_first_line_marker, glob_match, FIRST_LINE_MARKERS},
+};
 
 /// A `Container` can be used to implement a storage that holds [`Language`] and [`HeuristicRule`] definitions.
 ///
 /// ## Features
 /// When the `matcher` feature is enabled, the `Container` trait will also expose methods to retrieve [`HeuristicRule`] definitions.
+///
+/// ## Custom storage
+/// A container does not have to hold all definitions in memory. Heuristic rules can be returned as
+/// owned values, e.g., after loading them from a database for the requested extension; note that
+/// owned rules compile their patterns on every lookup.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use std::path::Path;
+///
+/// use linguist::container::Container;
+/// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+///
+/// /// Keeps the languages in memory, but loads the heuristics of an extension on demand.
+/// struct RemoteContainer {
+///     languages: Vec<Language>,
+///     // stands in for a database table of `(extension, language, pattern)` rows
+///     rows: Vec<(&'static str, &'static str, &'static str)>,
+/// }
+///
+/// impl Container for RemoteContainer {
+///     fn get_language_by_name(&self, name: &str) -> Option<&Language> {
+///         self.languages.iter().find(|lang| lang.name.eq_ignore_ascii_case(name))
+///     }
+///
+///     fn iter_languages(&self) -> impl Iterator<Item = &Language> + '_ {
+///         self.languages.iter()
+///     }
+///
+///     fn get_languages_by_extension(&self, file: impl AsRef<Path>) -> Option<Vec<&Language>> {
+///         let ext = file.as_ref().extension()?;
+///         let langs: Vec<&Language> = self
+///             .languages
+///             .iter()
+///             .filter(|lang| lang.extensions.iter().any(|e| e == ext))
+///             .collect();
+///         (!langs.is_empty()).then_some(langs)
+///     }
+///
+///     fn get_languages_by_filename(&self, _file: impl AsRef<Path>) -> Option<Vec<&Language>> {
+///         None
+///     }
+///
+///     fn get_languages_by_interpreter(&self, _interpreter: &str) -> Option<Vec<&Language>> {
+///         None
+///     }
+///
+///     fn get_heuristics_by_extension(
+///         &self,
+///         file: impl AsRef<Path>,
+///     ) -> Option<Cow<'_, [HeuristicRule]>> {
+///         let ext = file.as_ref().extension()?.to_str()?;
+///         let rules: Vec<HeuristicRule> = self
+///             .rows
+///             .iter()
+///             .filter(|(row_ext, _, _)| *row_ext == ext)
+///             .map(|(_, lang, pattern)| {
+///                 HeuristicRule::new(lang.to_string(), vec![ext.into()], vec![pattern.to_string()])
+///             })
+///             .collect();
+///         (!rules.is_empty()).then_some(Cow::Owned(rules))
+///     }
+/// }
+///
+/// let container = RemoteContainer {
+///     languages: vec![
+///         Language::new("MATLAB").extensions(["m"]),
+///         Language::new("Objective-C").extensions(["m"]),
+///     ],
+///     rows: vec![("m", "Objective-C", r"^\s*@interface\b")],
+/// };
+///
+/// let lang = resolve_language("tests/fixtures/ambiguous/Foundation.m", &container).unwrap();
+/// assert_eq!(lang.unwrap().name, "Objective-C");
+/// ```
 pub trait Container {
-    /// Returns a list of all [`Language`] definitions identified by its name.
+    /// Returns a list of all [`Language`] definitions identified by its name. Names are compared
+    /// ignoring ASCII case only, i.e., non-ASCII characters have to match exactly. If no language
+    /// has the given name, its aliases are considered, so an exact name always wins over an
+    /// alias of another language.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Rust").aliases(["rs"]),
+    ///     Language::new("RS").extensions(["rs"]),
+    ///     Language::new("JavaScript").aliases(["js", "node"]),
+    /// ]);
+    ///
+    /// assert_eq!(container.get_language_by_name("rust").unwrap().name, "Rust");
+    /// assert_eq!(container.get_language_by_name("Node").unwrap().name, "JavaScript");
+    /// // the name of `RS` wins over the alias of Rust
+    /// assert_eq!(container.get_language_by_name("rs").unwrap().name, "RS");
+    /// ```
     fn get_language_by_name(&self, name: &str) -> Option<&Language>;
+    /// Returns an iterator over all registered [`Language`] definitions, e.g., to list the
+    /// supported languages.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Rust").extensions(["rs"]),
+    ///     Language::new("TOML").extensions(["toml"]),
+    /// ]);
+    ///
+    /// let names: Vec<&str> = container.iter_languages().map(|lang| lang.name.as_str()).collect();
+    /// assert_eq!(names, ["Rust", "TOML"]);
+    /// assert_eq!(container.language_count(), 2);
+    /// ```
+    fn iter_languages(&self) -> impl Iterator<Item = &Language> + '_;
+    /// Returns the number of registered [`Language`] definitions, see
+    /// [`Container::iter_languages`].
+    fn language_count(&self) -> usize {
+        self.iter_languages().count()
+    }
+    /// Returns a list of all [`Language`] definitions of the given [`Scope`], e.g., to only
+    /// consider programming languages.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::{Language, Scope};
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Rust").scope(Scope::Programming),
+    ///     Language::new("Markdown").scope(Scope::Prose),
+    ///     Language::new("Go").scope(Scope::Programming),
+    /// ]);
+    ///
+    /// let names: Vec<&str> = container
+    ///     .get_languages_by_scope(&Scope::Programming)
+    ///     .iter()
+    ///     .map(|lang| lang.name.as_str())
+    ///     .collect();
+    /// assert_eq!(names, ["Rust", "Go"]);
+    /// assert!(container.get_languages_by_scope(&Scope::Data).is_empty());
+    /// ```
+    fn get_languages_by_scope(&self, scope: &Scope) -> Vec<&Language> {
+        self.iter_languages()
+            .filter(|lang| &lang.scope == scope)
+            .collect()
+    }
+    /// Returns the first [`Language`] with the given color, e.g., to render a language by its
+    /// GitHub color. Colors are compared ignoring ASCII case and the leading `#`.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language {
+    ///         color: Some("#dea584".to_string()),
+    ///         ..Language::new("Rust")
+    ///     },
+    ///     Language::new("Text"),
+    /// ]);
+    ///
+    /// assert_eq!(container.get_language_by_color("DEA584").unwrap().name, "Rust");
+    /// assert_eq!(container.get_language_by_color("#dea584").unwrap().name, "Rust");
+    /// assert!(container.get_language_by_color("#000000").is_none());
+    /// ```
+    fn get_language_by_color(&self, hex: &str) -> Option<&Language> {
+        let key = color_key(hex);
+        self.iter_languages().find(|lang| {
+            lang.color
+                .as_deref()
+                .is_some_and(|color| color_key(color) == key)
+        })
+    }
     /// Returns a list of all [`Language`] definitions identified by the extension of the given file.
+    /// Files without an extension, including dotfiles like `.gitignore`, never match an extension;
+    /// they are resolved by [`Container::get_languages_by_filename`] instead. Like GitHub's
+    /// Linguist, extensions are compared ignoring ASCII case, e.g., `Main.JAVA` matches `java`.
+    /// A file with several extensions matches the most specific one, e.g., `index.d.ts` matches
+    /// `d.ts` before `ts`.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Ignore List").extensions(["gitignore"]).filenames([".gitignore"]),
+    ///     Language::new("Rust").extensions(["rs"]),
+    /// ]);
+    ///
+    /// assert!(container.get_languages_by_extension(".gitignore").is_none());
+    /// assert!(container.get_languages_by_filename(".gitignore").is_some());
+    ///
+    /// assert!(container.get_languages_by_extension("src/rs").is_none());
+    /// assert!(container.get_languages_by_extension("src/main.rs").is_some());
+    /// assert!(container.get_languages_by_extension("src/MAIN.RS").is_some());
+    ///
+    /// // an empty extension, e.g., of `archive.`, never matches
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Unknown").extensions([""]),
+    ///     Language::new("INI").extensions(["conf", "weird"]).filenames([".hidden.conf"]),
+    /// ]);
+    /// assert!(container.get_languages_by_extension("archive.").is_none());
+    ///
+    /// // a dotfile with an extension has both a filename and an extension
+    /// assert!(container.get_languages_by_filename(".hidden.conf").is_some());
+    /// assert!(container.get_languages_by_extension(".hidden.conf").is_some());
+    /// assert!(container.get_languages_by_extension("..weird").is_some());
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("TypeScript").extensions(["ts"]),
+    ///     Language::new("TypeScript Declaration").extensions(["d.ts"]),
+    /// ]);
+    /// let names = |file| {
+    ///     container
+    ///         .get_languages_by_extension(file)
+    ///         .unwrap()
+    ///         .into_iter()
+    ///         .map(|lang| lang.name.as_str())
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(names("types/index.d.ts"), ["TypeScript Declaration"]);
+    /// assert_eq!(names("src/index.ts"), ["TypeScript"]);
+    /// assert_eq!(names("src/index.test.ts"), ["TypeScript"]);
+    /// ```
     fn get_languages_by_extension(&self, file: impl AsRef<Path>) -> Option<Vec<&Language>>;
     /// Returns a list of all [`Language`] definitions identified by the name of the given file.
+    /// The [`InMemoryLanguageContainer`] prefers filenames of the exact case and falls back to
+    /// comparing them ignoring ASCII case, so `dockerfile` matches a `Dockerfile`.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_languages([
+    ///     Language::new("Dockerfile").filenames(["Dockerfile"]),
+    ///     Language::new("Makefile").filenames(["Makefile"]),
+    ///     Language::new("Text").filenames(["makefile"]),
+    /// ]);
+    ///
+    /// let names = |file| {
+    ///     container
+    ///         .get_languages_by_filename(file)
+    ///         .unwrap_or_default()
+    ///         .iter()
+    ///         .map(|lang| lang.name.as_str())
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(names("dockerfile"), ["Dockerfile"]);
+    /// assert_eq!(names("makefile"), ["Text"]);
+    /// assert_eq!(names("MAKEFILE"), ["Makefile", "Text"]);
+    /// assert!(names("Dockerfile.dev").is_empty());
+    /// ```
     fn get_languages_by_filename(&self, file: impl AsRef<Path>) -> Option<Vec<&Language>>;
     /// Returns a list of all [`Language`] definitions identified by its interpreter.
     fn get_languages_by_interpreter(&self, interpreter: &str) -> Option<Vec<&Language>>;
     /// Returns a list of all [`HeuristicRule`] definitions identified by the extension of the given file.
+    /// The rules can be borrowed from the container or, e.g., loaded on demand from a database.
     #[cfg(feature = "matcher")]
-    fn get_heuristics_by_extension(&self, file: impl AsRef<Path>) -> Option<&Vec<HeuristicRule>>;
+    fn get_heuristics_by_extension(
+        &self,
+        file: impl AsRef<Path>,
+    ) -> Option<Cow<'_, [HeuristicRule]>>;
+
+    /// Returns the position at which the given [`Language`] was registered, which orders tied
+    /// candidates with [`TieBreak::FirstRegistered`](crate::resolver::TieBreak::FirstRegistered).
+    /// By default, the registration order is unknown, so these candidates are ordered by name.
+    fn registration_index(&self, _language: &Language) -> Option<usize> {
+        None
+    }
+
+    /// Returns the [`Language`] identified by a marker at the beginning of the given first line of
+    /// a file, e.g., `#cloud-config`. By default, the built-in
+    /// [`FIRST_LINE_MARKERS`](crate::utils::FIRST_LINE_MARKERS) are used.
+    fn get_language_by_first_line(&self, line: &str) -> Option<&Language> {
+        detect_first_line_marker(line).and_then(|name| self.get_language_by_name(name))
+    }
+
+    /// Returns an iterator over all [`Language`] definitions identified by the extension of the
+    /// given file. Unlike [`Container::get_languages_by_extension`], implementations can avoid
+    /// allocating a list, so callers that only need the first match can stop early.
+    fn languages_by_extension_iter<'a: 'b, 'b>(
+        &'a self,
+        file: &'b Path,
+    ) -> impl Iterator<Item = &'a Language> + 'b {
+        self.get_languages_by_extension(file).into_iter().flatten()
+    }
+
+    /// Returns an iterator over all [`Language`] definitions identified by the name of the given
+    /// file. See [`Container::languages_by_extension_iter`].
+    fn languages_by_filename_iter<'a: 'b, 'b>(
+        &'a self,
+        file: &'b Path,
+    ) -> impl Iterator<Item = &'a Language> + 'b {
+        self.get_languages_by_filename(file).into_iter().flatten()
+    }
+
+    /// Returns an iterator over all [`Language`] definitions identified by its interpreter. See
+    /// [`Container::languages_by_extension_iter`].
+    fn languages_by_interpreter_iter<'a: 'b, 'b>(
+        &'a self,
+        interpreter: &'b str,
+    ) -> impl Iterator<Item = &'a Language> + 'b {
+        self.get_languages_by_interpreter(interpreter)
+            .into_iter()
+            .flatten()
+    }
 }
 
+/// A `ContainerSummary` describes how many definitions are loaded into a container, e.g., to
+/// diagnose a definition file that was only partially loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContainerSummary {
+    pub language_count: usize,
+    pub extension_count: usize,
+    pub filename_count: usize,
+    pub interpreter_count: usize,
+    pub heuristic_rule_count: usize,
+}
+
+impl Display for ContainerSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "languages:       {}", self.language_count)?;
+        writeln!(f, "extensions:      {}", self.extension_count)?;
+        writeln!(f, "filenames:       {}", self.filename_count)?;
+        writeln!(f, "interpreters:    {}", self.interpreter_count)?;
+        write!(f, "heuristic rules: {}", self.heuristic_rule_count)
+    }
+}
+
+/// An `InMemoryLanguageContainer` keeps all languages and heuristic rules in memory. Once set up,
+/// the container is not modified by resolving files, i.e., it is `Send` and `Sync` and can be
+/// shared across threads, e.g., behind an [`Arc`](std::sync::Arc) in a thread pool. Compiled
+/// heuristic patterns are shared by all threads, see
+/// [`HeuristicRule::matcher`](crate::resolver::HeuristicRule::matcher).
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+/// use std::sync::Arc;
+///
+/// fn assert_shareable<T: Send + Sync>() {}
+/// assert_shareable::<InMemoryLanguageContainer>();
+///
+/// let container = Arc::new(
+///     InMemoryLanguageContainer::builder()
+///         .languages(["Perl", "Raku"].map(|name| Language::new(name).extensions(["t"])))
+///         .heuristic_rule(HeuristicRule::new(
+///             "Raku".to_string(),
+///             vec!["t".into()],
+///             vec![r"^\s*use\s+v6\b".to_string()],
+///         ))
+///         .build(),
+/// );
+///
+/// let files = ["ambiguous/plan.t", "ambiguous/basic.t"];
+/// let handles: Vec<_> = files
+///     .into_iter()
+///     .map(|file| {
+///         let container = Arc::clone(&container);
+///         std::thread::spawn(move || {
+///             let path = format!("tests/fixtures/{}", file);
+///             let lang = resolve_language(path, &*container).unwrap().unwrap();
+///             lang.name.clone()
+///         })
+///     })
+///     .collect();
+///
+/// let names: Vec<String> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+/// assert_eq!(names, ["Raku", "Perl"]);
+/// ```
 #[derive(Debug, Default)]
 pub struct InMemoryLanguageContainer {
     languages: Vec<Language>,
+    ids: HashMap<u64, usize>,
+    colors: HashMap<String, Vec<usize>>,
     heuristics: HashMap<OsString, Vec<HeuristicRule>>,
+    urls: HashMap<String, String>,
+    first_line_markers: Vec<(String, String)>,
 }
 
 impl InMemoryLanguageContainer {
+    /// Creates a new container with the given languages, see [`ContainerBuilder`] to register
+    /// heuristic rules as well.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_languages([
+    ///     Language::new("Rust").extensions(["rs"]),
+    ///     Language::new("Go").extensions(["go"]),
+    /// ]);
+    /// assert_eq!(container.language_count(), 2);
+    /// ```
+    pub fn from_languages(languages: impl IntoIterator<Item = impl Into<Language>>) -> Self {
+        let mut container = InMemoryLanguageContainer::default();
+        container.register_languages(languages);
+        container
+    }
+
+    /// Returns a [`ContainerBuilder`] to set up a new container.
+    pub fn builder() -> ContainerBuilder {
+        ContainerBuilder::new()
+    }
+
     pub fn register_language(&mut self, lang: impl Into<Language>) {
-        self.languages.push(lang.into());
+        let lang = lang.into();
+        self.index_language(&lang, self.languages.len());
+        self.languages.push(lang);
+    }
+
+    /// Used internally to add the language at the given index to the id and color indices.
+    fn index_language(&mut self, lang: &Language, index: usize) {
+        if let Some(id) = lang.language_id {
+            // like lookups by name, the first language with an id wins
+            self.ids.entry(id).or_insert(index);
+        }
+        if let Some(color) = &lang.color {
+            self.colors.entry(color_key(color)).or_default().push(index);
+        }
+    }
+
+    /// Registers all of the given languages, see [`register_language`].
+    ///
+    /// [`register_language`]: InMemoryLanguageContainer::register_language
+    pub fn register_languages(&mut self, languages: impl IntoIterator<Item = impl Into<Language>>) {
+        for lang in languages {
+            self.register_language(lang);
+        }
     }
 
+    /// Removes the first language with the given name, compared ignoring ASCII case, and returns
+    /// it. Once no language with the name is left, the heuristic rules, the reference URL, and the
+    /// first-line markers of the language are removed as well, so it can no longer be resolved.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+    ///
+    /// let mut container = InMemoryLanguageContainer::builder()
+    ///     .languages(["Coq", "Verilog"].map(|name| Language::new(name).extensions(["v"])))
+    ///     .heuristic_rule(HeuristicRule::new(
+    ///         "Verilog".to_string(),
+    ///         vec!["v".into()],
+    ///         vec![r"^\s*module\b".to_string()],
+    ///     ))
+    ///     .build();
+    ///
+    /// let file = "tests/fixtures/ambiguous/counter.v";
+    /// assert_eq!(resolve_language(file, &container).unwrap().unwrap().name, "Verilog");
+    ///
+    /// assert_eq!(container.remove_language("verilog").unwrap().name, "Verilog");
+    /// assert!(container.remove_language("Verilog").is_none());
+    /// assert!(container.get_heuristics_by_extension("counter.v").is_none());
+    /// assert_eq!(resolve_language(file, &container).unwrap().unwrap().name, "Coq");
+    ///
+    /// container.clear();
+    /// assert_eq!(container.language_count(), 0);
+    /// ```
+    pub fn remove_language(&mut self, name: &str) -> Option<Language> {
+        let index = self
+            .languages
+            .iter()
+            .position(|lang| lang.name.eq_ignore_ascii_case(name))?;
+        let lang = self.languages.remove(index);
+
+        // the indices of all following languages have shifted
+        self.ids.clear();
+        self.colors.clear();
+        for (index, lang) in std::mem::take(&mut self.languages).into_iter().enumerate() {
+            self.index_language(&lang, index);
+            self.languages.push(lang);
+        }
+
+        if !self
+            .languages
+            .iter()
+            .any(|other| other.name.eq_ignore_ascii_case(&lang.name))
+        {
+            for rules in self.heuristics.values_mut() {
+                rules.retain(|rule| !rule.language.eq_ignore_ascii_case(&lang.name));
+            }
+            self.heuristics.retain(|_, rules| !rules.is_empty());
+            self.urls.remove(&lang.name.to_ascii_lowercase());
+            self.first_line_markers
+                .retain(|(_, language)| !language.eq_ignore_ascii_case(&lang.name));
+        }
+
+        Some(lang)
+    }
+
+    /// Removes all languages, heuristic rules, reference URLs, and first-line markers.
+    pub fn clear(&mut self) {
+        *self = InMemoryLanguageContainer::default();
+    }
+
+    /// Returns the language with the given GitHub Linguist id, see [`Language::language_id`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language {
+    ///         language_id: Some(327),
+    ///         ..Language::new("Rust")
+    ///     },
+    ///     Language::new("Text"),
+    /// ]);
+    ///
+    /// assert_eq!(container.get_language_by_id(327).unwrap().name, "Rust");
+    /// assert!(container.get_language_by_id(41).is_none());
+    /// ```
+    pub fn get_language_by_id(&self, id: u64) -> Option<&Language> {
+        self.ids.get(&id).map(|&index| &self.languages[index])
+    }
+
+    /// Returns a [`ContainerSummary`] of all registered definitions. Extensions, filenames, and
+    /// interpreters that are shared by several languages are counted once.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{HeuristicRule, Language};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// container.register_language(Language {
+    ///     name: "C".to_string(),
+    ///     extensions: vec!["c".into(), "h".into()],
+    ///     ..Default::default()
+    /// });
+    /// container.register_language(Language {
+    ///     name: "C++".to_string(),
+    ///     extensions: vec!["cpp".into(), "h".into()],
+    ///     ..Default::default()
+    /// });
+    /// container.register_language(Language {
+    ///     name: "Shell".to_string(),
+    ///     filenames: vec![".bashrc".into()],
+    ///     interpreters: vec!["bash".into(), "sh".into()],
+    ///     ..Default::default()
+    /// });
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "C++".to_string(),
+    ///     vec!["h".into()],
+    ///     vec![r"^\s*template\s*<".to_string()],
+    /// ));
+    ///
+    /// let summary = container.summary();
+    /// assert_eq!(summary.language_count, 3);
+    /// assert_eq!(summary.extension_count, 3);
+    /// assert_eq!(summary.filename_count, 1);
+    /// assert_eq!(summary.interpreter_count, 2);
+    /// assert_eq!(summary.heuristic_rule_count, 1);
+    /// ```
+    pub fn summary(&self) -> ContainerSummary {
+        let extensions: HashSet<&OsString> = self
+            .languages
+            .iter()
+            .flat_map(|lang| lang.extensions.iter())
+            .collect();
+        let filenames: HashSet<&OsString> = self
+            .languages
+            .iter()
+            .flat_map(|lang| lang.filenames.iter())
+            .collect();
+        let interpreters: HashSet<&String> = self
+            .languages
+            .iter()
+            .flat_map(|lang| lang.interpreters.iter())
+            .collect();
+
+        let mut rules: Vec<&HeuristicRule> = Vec::new();
+        for rule in self.heuristics.values().flatten() {
+            if !rules.contains(&rule) {
+                rules.push(rule);
+            }
+        }
+
+        ContainerSummary {
+            language_count: self.languages.len(),
+            extension_count: extensions.len(),
+            filename_count: filenames.len(),
+            interpreter_count: interpreters.len(),
+            heuristic_rule_count: rules.len(),
+        }
+    }
+
+    /// Returns all languages that cannot be detected, i.e., languages without extensions,
+    /// filenames, and interpreters that are neither referenced by a heuristic rule nor by a
+    /// first-line marker. This usually points to a bug in the definitions.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{HeuristicRule, Language};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for name in ["Rust", "Cuneiform", "Hieroglyphs"] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         ..Default::default()
+    ///     });
+    /// }
+    /// container.register_language(Language {
+    ///     name: "C".to_string(),
+    ///     extensions: vec!["c".into()],
+    ///     ..Default::default()
+    /// });
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "Rust".to_string(),
+    ///     vec!["rs".into()],
+    ///     vec![r"\bfn\s+main\b".to_string()],
+    /// ));
+    /// container.register_first_line_marker("%cuneiform", "Cuneiform");
+    ///
+    /// let names: Vec<&str> = container
+    ///     .undetectable_languages()
+    ///     .iter()
+    ///     .map(|lang| lang.name.as_str())
+    ///     .collect();
+    /// assert_eq!(names, ["Hieroglyphs"]);
+    /// ```
+    pub fn undetectable_languages(&self) -> Vec<&Language> {
+        self.languages
+            .iter()
+            .filter(|lang| {
+                lang.extensions.is_empty()
+                    && lang.filenames.is_empty()
+                    && lang.filename_patterns.is_empty()
+                    && lang.interpreters.is_empty()
+            })
+            .filter(|lang| {
+                let has_rule = self
+                    .heuristics
+                    .values()
+                    .flatten()
+                    .any(|rule| rule.language.eq_ignore_ascii_case(&lang.name));
+                let has_marker = self
+                    .first_line_markers
+                    .iter()
+                    .map(|(_, language)| language.as_str())
+                    .chain(FIRST_LINE_MARKERS.iter().map(|&(_, language)| language))
+                    .any(|language| language.eq_ignore_ascii_case(&lang.name));
+
+                !has_rule && !has_marker
+            })
+            .collect()
+    }
+
+    /// Returns all languages with the given display color, e.g., to detect color collisions. The
+    /// color is compared as a hex code ignoring case and a leading `#`. The colors are indexed as
+    /// languages are registered, and the first match is returned by
+    /// [`Container::get_language_by_color`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let colored = |name: &str, color: &str| Language {
+    ///     color: Some(color.to_string()),
+    ///     ..Language::new(name)
+    /// };
+    /// let mut container = InMemoryLanguageContainer::from_iter([
+    ///     colored("TypeScript", "#3178c6"),
+    ///     colored("TSX", "#3178C6"),
+    ///     colored("Rust", "#dea584"),
+    ///     Language::new("Text"),
+    /// ]);
+    ///
+    /// let names: Vec<&str> = container
+    ///     .languages_by_color("3178C6")
+    ///     .iter()
+    ///     .map(|lang| lang.name.as_str())
+    ///     .collect();
+    /// assert_eq!(names, ["TypeScript", "TSX"]);
+    /// assert!(container.languages_by_color("#000000").is_empty());
+    ///
+    /// container.remove_language("TypeScript");
+    /// assert_eq!(container.get_language_by_color("#3178c6").unwrap().name, "TSX");
+    /// assert_eq!(container.get_language_by_color("DEA584").unwrap().name, "Rust");
+    /// ```
+    pub fn languages_by_color(&self, hex: &str) -> Vec<&Language> {
+        self.colors
+            .get(&color_key(hex))
+            .map(|indices| {
+                indices
+                    .iter()
+                    .map(|&index| &self.languages[index])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Registers a reference URL, e.g., to the documentation, for the language with the given name.
+    /// Like [`Container::get_language_by_name`], the name is looked up ignoring ASCII case.
+    pub fn register_language_url(&mut self, name: &str, url: &str) {
+        self.urls.insert(name.to_ascii_lowercase(), url.to_string());
+    }
+
+    /// Returns the reference URL of the language with the given name, if one has been registered.
+    pub fn get_language_url(&self, name: &str) -> Option<&str> {
+        self.urls
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Registers a marker that identifies the language with the given name if the first line of a
+    /// file starts with it. Registered markers take precedence over the built-in
+    /// [`FIRST_LINE_MARKERS`](crate::utils::FIRST_LINE_MARKERS).
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language, Language, Scope};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for name in ["YAML", "Swift", "Jsonnet"] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         scope: Scope::Programming,
+    ///         ..Default::default()
+    ///     });
+    /// }
+    /// container.register_first_line_marker("// jsonnet", "Jsonnet");
+    ///
+    /// let resolve = |file| resolve_language(file, &container).unwrap().unwrap().name.as_str();
+    /// assert_eq!(resolve("tests/fixtures/markers/user-data"), "YAML");
+    /// assert_eq!(resolve("tests/fixtures/markers/Package"), "Swift");
+    /// assert_eq!(resolve("tests/fixtures/markers/config"), "Jsonnet");
+    /// ```
+    pub fn register_first_line_marker(&mut self, marker: &str, language: &str) {
+        self.first_line_markers
+            .push((marker.to_string(), language.to_string()));
+    }
+
+    /// Registers all of the given heuristic rules, see [`register_heuristic_rule`].
+    ///
+    /// [`register_heuristic_rule`]: InMemoryLanguageContainer::register_heuristic_rule
+    #[cfg(feature = "matcher")]
+    pub fn register_heuristic_rules(
+        &mut self,
+        rules: impl IntoIterator<Item = impl Into<HeuristicRule>>,
+    ) {
+        for rule in rules {
+            self.register_heuristic_rule(rule);
+        }
+    }
+
+    /// Registers the given rule for all of its extensions. The patterns of the rule are compiled
+    /// once on registration and shared by all extensions, so resolving a file only runs the
+    /// matches. A rule whose patterns cannot be compiled is registered anyway and reports the
+    /// error when it is applied, see [`HeuristicRule::matcher`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::HeuristicRule;
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "C++".to_string(),
+    ///     vec!["h".into(), "hpp".into()],
+    ///     vec![r"^\s*template\s*<".to_string()],
+    /// ));
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "Broken".to_string(),
+    ///     vec!["h".into()],
+    ///     vec!["(unclosed".to_string()],
+    /// ));
+    ///
+    /// let rules = container.get_heuristics_by_extension("vector.h").unwrap();
+    /// assert!(rules[0].is_match("template <typename T>").unwrap());
+    /// assert!(rules[1].is_match("anything").is_err());
+    /// ```
     #[cfg(feature = "matcher")]
     pub fn register_heuristic_rule(&mut self, rule: impl Into<HeuristicRule>) {
         let rule = rule.into();
+        // compile the patterns before the rule is cloned, so all extensions share the result
+        let _ = rule.matcher();
 
         for ext in &rule.extensions {
-            if let Some(heuristic) = self.heuristics.get_mut(ext) {
+            let ext = ext.to_ascii_lowercase();
+            if let Some(heuristic) = self.heuristics.get_mut(&ext) {
                 if !heuristic.contains(&rule) {
                     heuristic.push(rule.clone());
-                } else {
                 }
             } else {
-                self.heuristics
-                    .insert(ext.to_os_string(), vec![rule.clone()]);
+                self.heuristics.insert(ext, vec![rule.clone()]);
+            }
+        }
+    }
+
+    /// Merges the given rule into the rules registered for the same language and extension, e.g.,
+    /// to layer additional heuristics onto GitHub's definitions. With [`MergePolicy::Append`], the
+    /// rule is inserted right after the existing rules of its language, so either of them can
+    /// match; with [`MergePolicy::Replace`], it takes the place of the existing rules. Without an
+    /// existing rule, the rule is registered like with [`register_heuristic_rule`]. Language names
+    /// are compared ignoring ASCII case.
+    ///
+    /// [`register_heuristic_rule`]: InMemoryLanguageContainer::register_heuristic_rule
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{InMemoryLanguageContainer, MergePolicy};
+    /// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for name in ["C", "C++", "Objective-C"] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         extensions: vec!["h".into()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "Objective-C".to_string(),
+    ///     vec!["h".into()],
+    ///     vec![r#"^\s*(@(interface|class|protocol|property|end)\b|#import\s+.+\.h[">])"#.to_string()],
+    /// ));
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "C++".to_string(),
+    ///     vec!["h".into()],
+    ///     vec![r"^\s*template\s*<".to_string()],
+    /// ));
+    ///
+    /// let extra = HeuristicRule::new(
+    ///     "Objective-C".to_string(),
+    ///     vec!["h".into()],
+    ///     vec![r"^NS_ASSUME_NONNULL_BEGIN".to_string()],
+    /// );
+    /// container.merge_heuristic_rule(extra, MergePolicy::Append);
+    ///
+    /// let resolve = |file| resolve_language(file, &container).unwrap().unwrap().name.as_str();
+    /// assert_eq!(resolve("tests/fixtures/heuristics/view.h"), "Objective-C");
+    /// assert_eq!(resolve("tests/fixtures/heuristics/analytics.h"), "Objective-C");
+    /// assert_eq!(resolve("tests/fixtures/heuristics/vector.h"), "C++");
+    /// ```
+    #[cfg(feature = "matcher")]
+    pub fn merge_heuristic_rule(&mut self, rule: impl Into<HeuristicRule>, policy: MergePolicy) {
+        let rule = rule.into();
+        let _ = rule.matcher();
+
+        for ext in &rule.extensions {
+            let heuristic = self.heuristics.entry(ext.to_ascii_lowercase()).or_default();
+            if heuristic.contains(&rule) {
+                continue;
+            }
+
+            let position = match policy {
+                MergePolicy::Append => heuristic
+                    .iter()
+                    .rposition(|existing| existing.language.eq_ignore_ascii_case(&rule.language))
+                    .map(|pos| pos + 1),
+                MergePolicy::Replace => {
+                    let position = heuristic.iter().position(|existing| {
+                        existing.language.eq_ignore_ascii_case(&rule.language)
+                    });
+                    heuristic
+                        .retain(|existing| !existing.language.eq_ignore_ascii_case(&rule.language));
+                    position
+                }
+            };
+
+            match position {
+                Some(pos) => heuristic.insert(pos, rule.clone()),
+                None => heuristic.push(rule.clone()),
             }
         }
     }
 }
 
+/// A `MergePolicy` decides how [`InMemoryLanguageContainer::merge_heuristic_rule`] treats the
+/// rules that are already registered for the same language and extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the existing rules and add the new rule as an alternative.
+    #[default]
+    Append,
+    /// Drop the existing rules in favor of the new rule.
+    Replace,
+}
+
+/// Used internally to serialize the definitions of an [`InMemoryLanguageContainer`]. Heuristic
+/// rules are stored once, even if they apply to several extensions.
+#[cfg(feature = "bincode")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Snapshot {
+    languages: Vec<Language>,
+    /// Every rule once, so rules that are registered for several extensions share their compiled
+    /// patterns after loading.
+    rules: Vec<HeuristicRule>,
+    /// The indices of the rules of each extension in their registration order, ordered by
+    /// extension, so the snapshot does not depend on the order of the `HashMap`.
+    heuristics: Vec<(OsString, Vec<usize>)>,
+    urls: BTreeMap<String, String>,
+    first_line_markers: Vec<(String, String)>,
+}
+
+#[cfg(feature = "bincode")]
+impl InMemoryLanguageContainer {
+    /// Serializes all registered definitions into a compact binary snapshot, which can be loaded
+    /// with [`InMemoryLanguageContainer::from_bincode`] much faster than the YAML definitions. The
+    /// snapshot is deterministic and keeps the order of the heuristic rules of every extension,
+    /// which decides between rules that match equally well.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for name in ["Perl", "Raku"] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         extensions: vec!["t".into()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "Raku".to_string(),
+    ///     vec!["t".into(), "rakutest".into()],
+    ///     vec![r"^\s*use\s+v6\b".to_string()],
+    /// ));
+    ///
+    /// let snapshot = container.to_bincode().unwrap();
+    /// let restored = InMemoryLanguageContainer::from_bincode(&snapshot).unwrap();
+    /// assert_eq!(restored.summary(), container.summary());
+    /// assert_eq!(restored.get_language_by_name("Raku"), container.get_language_by_name("Raku"));
+    ///
+    /// let lang = resolve_language("tests/fixtures/ambiguous/plan.t", &restored).unwrap();
+    /// assert_eq!(lang.unwrap().name, "Raku");
+    /// ```
+    ///
+    /// Tied rules resolve to the same language before and after the round trip:
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language_from_bytes, HeuristicRule, Language};
+    ///
+    /// let build = || {
+    ///     InMemoryLanguageContainer::builder()
+    ///         .languages(["A", "B"].map(|name| Language::new(name).extensions(["x", "y"])))
+    ///         .heuristic_rule(HeuristicRule::new(
+    ///             "B".to_string(),
+    ///             vec!["x".into()],
+    ///             vec!["foo".to_string()],
+    ///         ))
+    ///         .heuristic_rule(HeuristicRule::new(
+    ///             "A".to_string(),
+    ///             vec!["y".into(), "x".into()],
+    ///             vec!["foo".to_string()],
+    ///         ))
+    ///         .build()
+    /// };
+    ///
+    /// let container = build();
+    /// let snapshot = container.to_bincode().unwrap();
+    /// assert!((0..10).all(|_| build().to_bincode().unwrap() == snapshot));
+    ///
+    /// let restored = InMemoryLanguageContainer::from_bincode(&snapshot).unwrap();
+    /// for container in [&container, &restored] {
+    ///     let lang = resolve_language_from_bytes("f.x", b"foo", container).unwrap();
+    ///     assert_eq!(lang.unwrap().name, "B");
+    /// }
+    /// ```
+    pub fn to_bincode(&self) -> Result<Vec<u8>, LinguistError> {
+        let mut extensions: Vec<&OsString> = self.heuristics.keys().collect();
+        extensions.sort();
+
+        let mut rules: Vec<HeuristicRule> = Vec::new();
+        let mut heuristics = Vec::new();
+        for ext in extensions {
+            let indices = self.heuristics[ext]
+                .iter()
+                .map(|rule| match rules.iter().position(|other| other == rule) {
+                    Some(index) => index,
+                    None => {
+                        rules.push(rule.clone());
+                        rules.len() - 1
+                    }
+                })
+                .collect();
+            heuristics.push((ext.clone(), indices));
+        }
+
+        let snapshot = Snapshot {
+            languages: self.languages.clone(),
+            rules,
+            heuristics,
+            urls: self.urls.clone().into_iter().collect(),
+            first_line_markers: self.first_line_markers.clone(),
+        };
+
+        bincode::serialize(&snapshot).map_err(|_| LinguistError::SerializationError)
+    }
+
+    /// Loads a container from a snapshot created by [`InMemoryLanguageContainer::to_bincode`].
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self, LinguistError> {
+        let snapshot: Snapshot =
+            bincode::deserialize(bytes).map_err(|_| LinguistError::DeserializationError)?;
+
+        // compile the patterns before the rules are cloned, so all extensions share the result
+        #[cfg(feature = "matcher")]
+        for rule in snapshot.rules.iter() {
+            let _ = rule.matcher();
+        }
+
+        let mut container = InMemoryLanguageContainer {
+            urls: snapshot.urls.into_iter().collect(),
+            first_line_markers: snapshot.first_line_markers,
+            ..Default::default()
+        };
+        container.extend(snapshot.languages);
+        for (ext, indices) in snapshot.heuristics {
+            let rules = indices
+                .into_iter()
+                .map(|index| snapshot.rules.get(index).cloned())
+                .collect::<Option<Vec<HeuristicRule>>>()
+                .ok_or(LinguistError::DeserializationError)?;
+            container.heuristics.insert(ext, rules);
+        }
+
+        Ok(container)
+    }
+
+    /// Writes a binary snapshot of the container to the given file, see
+    /// [`InMemoryLanguageContainer::to_bincode`]. The snapshot can be created at build time and
+    /// loaded at startup with [`InMemoryLanguageContainer::load`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::error::LinguistError;
+    /// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+    ///
+    /// let container = InMemoryLanguageContainer::builder()
+    ///     .languages(["MATLAB", "Objective-C"].map(|name| Language::new(name).extensions(["m"])))
+    ///     .heuristic_rule(HeuristicRule::new(
+    ///         "Objective-C".to_string(),
+    ///         vec!["m".into()],
+    ///         vec![r"^\s*@interface\b".to_string()],
+    ///     ))
+    ///     .build();
+    ///
+    /// let path = std::env::temp_dir().join("linguist-container.bin");
+    /// container.save(&path).unwrap();
+    ///
+    /// let restored = InMemoryLanguageContainer::load(&path).unwrap();
+    /// assert_eq!(restored.summary(), container.summary());
+    /// let lang = resolve_language("tests/fixtures/ambiguous/Foundation.m", &restored).unwrap();
+    /// assert_eq!(lang.unwrap().name, "Objective-C");
+    ///
+    /// let missing = InMemoryLanguageContainer::load("tests/fixtures/missing.bin");
+    /// assert!(matches!(missing, Err(LinguistError::IOError(_))));
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LinguistError> {
+        std::fs::write(path, self.to_bincode()?)?;
+        Ok(())
+    }
+
+    /// Loads a container from a file written by [`InMemoryLanguageContainer::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LinguistError> {
+        Self::from_bincode(&std::fs::read(path)?)
+    }
+}
+
+/// Collects the given languages into a new container, e.g., to set up a small container inline.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language, Language, Scope};
+///
+/// let container = InMemoryLanguageContainer::from_iter([
+///     Language::new("C").scope(Scope::Programming).extensions(["c", "h"]),
+///     Language::new("C++").scope(Scope::Programming).extensions(["cpp", "h"]),
+///     Language::new("Shell").interpreters(["sh", "bash"]),
+/// ]);
+///
+/// // without heuristics, the ambiguous `.h` extension resolves by name
+/// let lang = resolve_language("tests/fixtures/heuristics/vector.h", &container).unwrap();
+/// assert_eq!(lang.unwrap().name, "C");
+/// ```
+impl<L: Into<Language>> FromIterator<L> for InMemoryLanguageContainer {
+    fn from_iter<I: IntoIterator<Item = L>>(iter: I) -> Self {
+        let mut container = InMemoryLanguageContainer::default();
+        container.extend(iter);
+        container
+    }
+}
+
+impl<L: Into<Language>> Extend<L> for InMemoryLanguageContainer {
+    fn extend<I: IntoIterator<Item = L>>(&mut self, iter: I) {
+        self.register_languages(iter);
+    }
+}
+
+/// A `ContainerBuilder` sets up an [`InMemoryLanguageContainer`] with languages and heuristic
+/// rules, e.g., the definitions generated by `linguist-build`.
+///
+/// # Example
+/// ```
+/// use linguist::container::ContainerBuilder;
+/// use linguist::resolver::{resolve_language, HeuristicRule, Language, Scope};
+///
+/// let rules = [
+///     HeuristicRule::new("Objective-C".to_string(), vec!["m".into()], vec!["^@interface".to_string()]),
+///     HeuristicRule::new("MATLAB".to_string(), vec!["m".into()], vec![r"^\s*function\b".to_string()]),
+/// ];
+/// let container = ContainerBuilder::new()
+///     .languages(["Mathematica", "MATLAB", "Objective-C"].map(|name| {
+///         Language::new(name).scope(Scope::Programming).extensions(["m"])
+///     }))
+///     .language(Language::new("Rust").extensions(["rs"]))
+///     .heuristic_rules(rules)
+///     .build();
+///
+/// let lang = resolve_language("tests/fixtures/ambiguous/average.m", &container).unwrap();
+/// assert_eq!(lang.unwrap().name, "MATLAB");
+/// ```
+#[derive(Debug, Default)]
+pub struct ContainerBuilder {
+    container: InMemoryLanguageContainer,
+}
+
+impl ContainerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given language, see [`InMemoryLanguageContainer::register_language`].
+    pub fn language(mut self, lang: impl Into<Language>) -> Self {
+        self.container.register_language(lang);
+        self
+    }
+
+    /// Adds all of the given languages.
+    pub fn languages(mut self, languages: impl IntoIterator<Item = impl Into<Language>>) -> Self {
+        self.container.register_languages(languages);
+        self
+    }
+
+    /// Adds the given heuristic rule, see [`InMemoryLanguageContainer::register_heuristic_rule`].
+    #[cfg(feature = "matcher")]
+    pub fn heuristic_rule(mut self, rule: impl Into<HeuristicRule>) -> Self {
+        self.container.register_heuristic_rule(rule);
+        self
+    }
+
+    /// Adds all of the given heuristic rules.
+    #[cfg(feature = "matcher")]
+    pub fn heuristic_rules(
+        mut self,
+        rules: impl IntoIterator<Item = impl Into<HeuristicRule>>,
+    ) -> Self {
+        self.container.register_heuristic_rules(rules);
+        self
+    }
+
+    /// Returns the container with all languages and heuristic rules that were added.
+    pub fn build(self) -> InMemoryLanguageContainer {
+        self.container
+    }
+}
+
+/// Used internally to normalize a hex color, so colors can be compared ignoring ASCII case and
+/// the leading `#`.
+fn color_key(hex: &str) -> String {
+    hex.trim().trim_start_matches('#').to_ascii_lowercase()
+}
+
+/// Used internally to determine the extension of the given file. An empty extension, e.g., of
+/// `archive.`, is treated like no extension at all.
+fn extension(file: &Path) -> Option<&OsStr> {
+    file.extension().filter(|ext| !ext.is_empty())
+}
+
+/// Used internally to determine all extensions of the given file, from the most specific to the
+/// least specific one, e.g., `d.ts` and `ts` for `index.d.ts`. Like [`extension`], the leading
+/// `.` of a dotfile is not the start of an extension.
+fn extensions(file: &Path) -> Vec<&OsStr> {
+    let ext = match extension(file) {
+        Some(ext) => ext,
+        _ => return Vec::new(),
+    };
+    // names that are not valid UTF-8 are only matched by their last extension
+    let name = match file.file_name().and_then(OsStr::to_str) {
+        Some(name) => name.strip_prefix('.').unwrap_or(name),
+        _ => return vec![ext],
+    };
+
+    name.match_indices('.')
+        .map(|(index, _)| OsStr::new(&name[index + 1..]))
+        .collect()
+}
+
+/// Used internally to check whether the given language lists the given extension, ignoring ASCII
+/// case.
+fn has_extension(lang: &Language, ext: &OsStr) -> bool {
+    lang.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+}
+
 impl Container for InMemoryLanguageContainer {
     fn get_language_by_name(&self, name: &str) -> Option<&Language> {
         self.languages
             .iter()
-            .find(|lang| lang.name.to_lowercase() == *name.to_lowercase())
+            .find(|lang| lang.name.eq_ignore_ascii_case(name))
+            .or_else(|| {
+                self.languages.iter().find(|lang| {
+                    lang.aliases
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(name))
+                })
+            })
     }
 
-    fn get_languages_by_extension(&self, file: impl AsRef<Path>) -> Option<Vec<&Language>> {
-        let ext = match file.as_ref().extension() {
-            Some(ext) => ext,
-            _ => match file.as_ref().file_name() {
-                Some(name) => name,
-                _ => return None,
-            },
-        };
+    fn iter_languages(&self) -> impl Iterator<Item = &Language> + '_ {
+        self.languages.iter()
+    }
 
-        let candidates: Vec<&Language> = self
-            .languages
+    fn language_count(&self) -> usize {
+        self.languages.len()
+    }
+
+    fn get_language_by_color(&self, hex: &str) -> Option<&Language> {
+        self.languages_by_color(hex).into_iter().next()
+    }
+
+    fn registration_index(&self, language: &Language) -> Option<usize> {
+        self.languages
             .iter()
-            .filter(|lang| lang.extensions.contains(&OsString::from(ext)))
-            .collect();
+            .position(|lang| std::ptr::eq(lang, language))
+    }
+
+    fn get_languages_by_extension(&self, file: impl AsRef<Path>) -> Option<Vec<&Language>> {
+        let candidates: Vec<&Language> = self.languages_by_extension_iter(file.as_ref()).collect();
 
         if !candidates.is_empty() {
             Some(candidates)
@@ -79,14 +1275,7 @@ impl Container for InMemoryLanguageContainer {
     }
 
     fn get_languages_by_filename(&self, file: impl AsRef<Path>) -> Option<Vec<&Language>> {
-        let candidates: Vec<&Language> = self
-            .languages
-            .iter()
-            .filter(|lang| {
-                lang.filenames
-                    .contains(&file.as_ref().as_os_str().to_os_string())
-            })
-            .collect();
+        let candidates: Vec<&Language> = self.languages_by_filename_iter(file.as_ref()).collect();
 
         if !candidates.is_empty() {
             Some(candidates)
@@ -96,22 +1285,19 @@ impl Container for InMemoryLanguageContainer {
     }
 
     #[cfg(feature = "matcher")]
-    fn get_heuristics_by_extension(&self, file: impl AsRef<Path>) -> Option<&Vec<HeuristicRule>> {
-        let ext = match file.as_ref().extension() {
-            Some(val) => val,
-            _ => return None,
-        };
-
-        let heuristics = self.heuristics.get(&ext.to_os_string());
-        heuristics
+    fn get_heuristics_by_extension(
+        &self,
+        file: impl AsRef<Path>,
+    ) -> Option<Cow<'_, [HeuristicRule]>> {
+        extensions(file.as_ref())
+            .into_iter()
+            .find_map(|ext| self.heuristics.get(&ext.to_ascii_lowercase()))
+            .map(|rules| Cow::Borrowed(rules.as_slice()))
     }
 
     fn get_languages_by_interpreter(&self, interpreter: &str) -> Option<Vec<&Language>> {
-        let interpreters: Vec<&Language> = self
-            .languages
-            .iter()
-            .filter(|lang| lang.interpreters.contains(&interpreter.to_string()))
-            .collect();
+        let interpreters: Vec<&Language> =
+            self.languages_by_interpreter_iter(interpreter).collect();
 
         if !interpreters.is_empty() {
             Some(interpreters)
@@ -119,4 +1305,72 @@ impl Container for InMemoryLanguageContainer {
             None
         }
     }
+
+    fn get_language_by_first_line(&self, line: &str) -> Option<&Language> {
+        let name = match self
+            .first_line_markers
+            .iter()
+            .find(|(marker, _)| line.starts_with(marker.as_str()))
+        {
+            Some((_, name)) => Some(name.as_str()),
+            _ => detect_first_line_marker(line),
+        };
+
+        name.and_then(|name| self.get_language_by_name(name))
+    }
+
+    fn languages_by_extension_iter<'a: 'b, 'b>(
+        &'a self,
+        file: &'b Path,
+    ) -> impl Iterator<Item = &'a Language> + 'b {
+        // dotfiles and files without an extension are left to the filename signal
+        let ext = extensions(file)
+            .into_iter()
+            .find(|ext| self.languages.iter().any(|lang| has_extension(lang, ext)));
+
+        self.languages
+            .iter()
+            .filter(move |lang| ext.is_some_and(|ext| has_extension(lang, ext)))
+    }
+
+    fn languages_by_filename_iter<'a: 'b, 'b>(
+        &'a self,
+        file: &'b Path,
+    ) -> impl Iterator<Item = &'a Language> + 'b {
+        let file = file.as_os_str();
+        // an exact match wins, otherwise e.g. `makefile` matches a `Makefile` as well
+        let exact = self
+            .languages
+            .iter()
+            .any(|lang| lang.filenames.iter().any(|name| name == file));
+        let ignore_case = !exact
+            && self.languages.iter().any(|lang| {
+                lang.filenames
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(file))
+            });
+        self.languages
+            .iter()
+            .filter(move |lang| match (exact, ignore_case) {
+                (true, _) => lang.filenames.iter().any(|name| name == file),
+                (_, true) => lang
+                    .filenames
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(file)),
+                _ => file.to_str().is_some_and(|file| {
+                    lang.filename_patterns
+                        .iter()
+                        .any(|pattern| glob_match(pattern, file))
+                }),
+            })
+    }
+
+    fn languages_by_interpreter_iter<'a: 'b, 'b>(
+        &'a self,
+        interpreter: &'b str,
+    ) -> impl Iterator<Item = &'a Language> + 'b {
+        self.languages
+            .iter()
+            .filter(move |lang| lang.interpreters.iter().any(|interp| interp == interpreter))
+    }
 }
@@ -1,8 +1,29 @@
+use std::fmt::Display;
+
+/// A `LinguistError` is returned by all fallible operations of this crate. It implements
+/// [`std::error::Error`], so it can be propagated with `?`, e.g., into a `Box<dyn Error>`.
+///
+/// # Example
+/// ```
+/// use std::error::Error;
+///
+/// use linguist::error::LinguistError;
+///
+/// let err = LinguistError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing.rs"));
+/// assert_eq!(err.to_string(), "an io error occurred: missing.rs");
+/// assert!(err.source().is_some());
+///
+/// let err: Box<dyn Error> = Box::new(LinguistError::LanguageNotFound);
+/// assert_eq!(err.to_string(), "the language could not be found");
+/// ```
 #[derive(Debug)]
 pub enum LinguistError {
     /// Indicates that the language definition file could not be deserialized.
     #[cfg(feature = "serde")]
     DeserializationError,
+    /// Indicates that a language definition could not be serialized.
+    #[cfg(feature = "serde")]
+    SerializationError,
     /// Indicates that a specific language cannot be found.
     LanguageNotFound,
     /// Indicates that a given file could not be found.
@@ -10,6 +31,9 @@ pub enum LinguistError {
     FileNotFound,
     /// Represents an error that occurred while compiling a regular expression.
     PatternCompileError(regex::Error),
+    /// Indicates that the contained pattern uses a construct that is not supported by the
+    /// [regex crate](https://crates.io/crates/regex), e.g., a lookahead or a backreference.
+    UnsupportedPattern(String),
     /// Represents an error occured concerning io stuff.
     IOError(std::io::Error),
 }
@@ -25,3 +49,38 @@ impl From<regex::Error> for LinguistError {
         LinguistError::PatternCompileError(value)
     }
 }
+
+impl Display for LinguistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "serde")]
+            LinguistError::DeserializationError => {
+                write!(f, "the language definitions could not be deserialized")
+            }
+            #[cfg(feature = "serde")]
+            LinguistError::SerializationError => {
+                write!(f, "the language definitions could not be serialized")
+            }
+            LinguistError::LanguageNotFound => write!(f, "the language could not be found"),
+            #[cfg(feature = "serde")]
+            LinguistError::FileNotFound => write!(f, "the definition file could not be found"),
+            LinguistError::PatternCompileError(err) => {
+                write!(f, "a pattern could not be compiled: {}", err)
+            }
+            LinguistError::UnsupportedPattern(pattern) => {
+                write!(f, "the pattern uses an unsupported construct: {}", pattern)
+            }
+            LinguistError::IOError(err) => write!(f, "an io error occurred: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LinguistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LinguistError::PatternCompileError(err) => Some(err),
+            LinguistError::IOError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use regex::Regex;
+
+use crate::error::LinguistError;
+
+/// The state of an attribute that is assigned to a path, see
+/// <https://git-scm.com/docs/gitattributes#_description>.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeState {
+    /// The attribute is set, e.g., `linguist-vendored`.
+    Set,
+    /// The attribute is unset, e.g., `-linguist-vendored`.
+    Unset,
+    /// The attribute is set to a value, e.g., `linguist-vendored=false`.
+    Value(String),
+}
+
+impl AttributeState {
+    /// Interprets the state as a boolean like Linguist does, i.e., a value of `false` is treated
+    /// like an unset attribute.
+    pub fn as_bool(&self) -> bool {
+        match self {
+            AttributeState::Set => true,
+            AttributeState::Unset => false,
+            AttributeState::Value(value) => value != "false",
+        }
+    }
+}
+
+/// A single line of a `.gitattributes` file, i.e., a pattern and the attributes it assigns.
+#[derive(Debug, Clone)]
+struct AttributeRule {
+    matcher: Regex,
+    attributes: Vec<(String, Option<AttributeState>)>,
+}
+
+/// `GitAttributes` hold the rules of a `.gitattributes` file. Later rules override earlier ones,
+/// like in git.
+#[derive(Debug, Clone, Default)]
+pub struct GitAttributes {
+    rules: Vec<AttributeRule>,
+}
+
+impl GitAttributes {
+    /// Returns the state of the given attribute for the given path, or `None` if no rule assigns
+    /// it. The path is expected to be relative to the directory of the `.gitattributes` file.
+    pub fn get(&self, file: impl AsRef<Path>, attribute: &str) -> Option<&AttributeState> {
+        let file = file.as_ref().to_string_lossy().replace('\\', "/");
+
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.matcher.is_match(&file))
+            .flat_map(|rule| rule.attributes.iter().rev())
+            .find(|(name, _)| name == attribute)
+            .and_then(|(_, state)| state.as_ref())
+    }
+
+    /// Returns whether the given path is marked as vendored (`linguist-vendored`) or explicitly
+    /// not vendored (`-linguist-vendored` or `linguist-vendored=false`).
+    pub fn vendored(&self, file: impl AsRef<Path>) -> Option<bool> {
+        self.get(file, "linguist-vendored")
+            .map(AttributeState::as_bool)
+    }
+
+    /// Returns whether the given path is marked as documentation (`linguist-documentation`) or
+    /// explicitly not documentation, like [`GitAttributes::vendored`].
+    pub fn documentation(&self, file: impl AsRef<Path>) -> Option<bool> {
+        self.get(file, "linguist-documentation")
+            .map(AttributeState::as_bool)
+    }
+
+    /// Returns whether the given path is marked as generated (`linguist-generated`) or explicitly
+    /// not generated, like [`GitAttributes::vendored`].
+    pub fn generated(&self, file: impl AsRef<Path>) -> Option<bool> {
+        self.get(file, "linguist-generated")
+            .map(AttributeState::as_bool)
+    }
+
+    /// Returns the name of the language that the given path is forced to by `linguist-language`,
+    /// e.g., `PHP` for `*.inc linguist-language=PHP`.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::gitattributes::parse_gitattributes;
+    ///
+    /// let content = "*.inc linguist-language=PHP\ndocs/** linguist-documentation\n";
+    /// let attributes = parse_gitattributes(content.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(attributes.language("lib/helpers.inc"), Some("PHP"));
+    /// assert_eq!(attributes.language("src/main.rs"), None);
+    /// assert_eq!(attributes.documentation("docs/guide.md"), Some(true));
+    /// assert_eq!(attributes.generated("docs/guide.md"), None);
+    /// ```
+    pub fn language(&self, file: impl AsRef<Path>) -> Option<&str> {
+        match self.get(file, "linguist-language")? {
+            AttributeState::Value(name) => Some(name.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the rules of a `.gitattributes` file. Empty lines and comments are skipped; macro
+/// definitions and quoted patterns are not supported.
+///
+/// # Example
+/// ```
+/// use linguist::gitattributes::parse_gitattributes;
+///
+/// let content = "# bundled code\nthird_party/** linguist-vendored\nvendor/** -linguist-vendored\n";
+/// let attributes = parse_gitattributes(content.as_bytes()).unwrap();
+///
+/// assert_eq!(attributes.vendored("third_party/zlib/inflate.c"), Some(true));
+/// assert_eq!(attributes.vendored("vendor/tiny-dom.js"), Some(false));
+/// assert_eq!(attributes.vendored("src/main.rs"), None);
+/// ```
+pub fn parse_gitattributes(reader: impl Read) -> Result<GitAttributes, LinguistError> {
+    let mut rules = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let pattern = match fields.next() {
+            Some(pattern) => pattern,
+            _ => continue,
+        };
+
+        let attributes = fields
+            .map(|field| {
+                if let Some(name) = field.strip_prefix('-') {
+                    (name.to_string(), Some(AttributeState::Unset))
+                } else if let Some(name) = field.strip_prefix('!') {
+                    (name.to_string(), None)
+                } else if let Some((name, value)) = field.split_once('=') {
+                    (
+                        name.to_string(),
+                        Some(AttributeState::Value(value.to_string())),
+                    )
+                } else {
+                    (field.to_string(), Some(AttributeState::Set))
+                }
+            })
+            .collect();
+
+        rules.push(AttributeRule {
+            matcher: Regex::new(&glob_to_regex(pattern))?,
+            attributes,
+        });
+    }
+
+    Ok(GitAttributes { rules })
+}
+
+/// Used internally to translate a gitattributes pattern into a regular expression. A pattern
+/// without a slash matches a file name in any directory; otherwise, the pattern is anchored at
+/// the directory of the `.gitattributes` file.
+fn glob_to_regex(pattern: &str) -> String {
+    let (anchored, pattern) = match pattern.strip_prefix('/') {
+        Some(pattern) => (true, pattern),
+        _ => (pattern.contains('/'), pattern),
+    };
+
+    let mut regex = String::from(if anchored { "^" } else { "(^|/)" });
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+
+    regex
+}
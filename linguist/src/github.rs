@@ -1,26 +1,48 @@
+use crate::classifier::Filters;
+#[cfg(feature = "matcher")]
+use crate::container::InMemoryLanguageContainer;
 use crate::error::LinguistError;
 use crate::resolver::{HeuristicRule, Language, Scope};
-use crate::serde::{deserialize_languages, deserialize_strings};
-use crate::utils::is_unsupported_regex_syntax;
+use crate::serde::{
+    deserialize_languages_dir, deserialize_languages_from_str, deserialize_string_map_from_str,
+    deserialize_strings_from_str,
+};
+use crate::utils::{check_pattern, is_popular, normalize_extension};
 use std::collections::HashMap;
 use std::ffi::OsString;
 
 use std::fmt::Display;
 use std::path::Path;
 
+use regex::RegexSet;
+
 /// Internal representation of a language definition from GitHub's Linguist.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct GitHubLanguage {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
     #[serde(skip)]
     pub name: String,
     #[serde(rename = "type")]
     pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub aliases: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub extensions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub filenames: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub interpreters: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tm_scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ace_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codemirror_mode: Option<String>,
 }
 
 impl TryInto<Language> for GitHubLanguage {
@@ -39,18 +61,82 @@ impl TryInto<Language> for GitHubLanguage {
                 .iter()
                 .map(OsString::from)
                 .collect(),
+            // GitHub's Linguist only defines exact filenames
+            filename_patterns: vec![],
             extensions: self
                 .extensions
                 .unwrap_or_default()
                 .iter()
-                .map(|ext| OsString::from(ext.replacen('.', "", 1)))
+                .map(|ext| normalize_extension(ext))
                 .collect(),
             interpreters: self.interpreters.unwrap_or_default(),
+            language_id: self.language_id,
+            tm_scope: self.tm_scope,
+            ace_mode: self.ace_mode,
+            codemirror_mode: self.codemirror_mode,
+            // the name is only known once the language is loaded, see `mark_popular`
+            popular: false,
         })
     }
 }
 
+impl From<&Language> for GitHubLanguage {
+    fn from(value: &Language) -> Self {
+        fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+            if items.is_empty() {
+                None
+            } else {
+                Some(items)
+            }
+        }
+
+        GitHubLanguage {
+            color: value.color.clone(),
+            name: value.name.clone(),
+            scope: match &value.scope {
+                Scope::Other(scope) => scope.clone(),
+                scope => scope.to_string().to_ascii_lowercase(),
+            },
+            aliases: non_empty(value.aliases.clone()),
+            extensions: non_empty(
+                value
+                    .extensions
+                    .iter()
+                    // GitHub's Linguist lists extensions with a leading `.`
+                    .map(|ext| format!(".{}", ext.to_string_lossy()))
+                    .collect(),
+            ),
+            filenames: non_empty(
+                value
+                    .filenames
+                    .iter()
+                    .map(|filename| filename.to_string_lossy().into_owned())
+                    .collect(),
+            ),
+            interpreters: non_empty(value.interpreters.clone()),
+            group: value.parent.clone(),
+            language_id: value.language_id,
+            tm_scope: value.tm_scope.clone(),
+            ace_mode: value.ace_mode.clone(),
+            codemirror_mode: value.codemirror_mode.clone(),
+        }
+    }
+}
+
 /// Loads all GitHub Linguist languages from the given file and returns list of [`Language`].
+/// Languages that are one of the [`POPULAR_LANGUAGES`](crate::utils::POPULAR_LANGUAGES) are
+/// marked as [`Language::popular`].
+///
+/// # Example
+/// ```
+/// use linguist::github::load_github_linguist_languages;
+///
+/// let languages = load_github_linguist_languages("tests/fixtures/github/languages.yml").unwrap();
+/// let popular = |name: &str| languages.iter().find(|lang| lang.name == name).unwrap().popular;
+/// assert!(popular("C"));
+/// assert!(popular("Rust"));
+/// assert!(!popular("CMake"));
+/// ```
 pub fn load_github_linguist_languages(
     path: impl AsRef<Path>,
 ) -> Result<Vec<Language>, LinguistError> {
@@ -58,8 +144,147 @@ pub fn load_github_linguist_languages(
         return Err(LinguistError::FileNotFound);
     }
 
-    let languages = deserialize_languages::<GitHubLanguage>(path)?;
-    Ok(languages)
+    load_github_linguist_languages_from_str(&std::fs::read_to_string(path)?)
+}
+
+/// Loads all GitHub Linguist languages from the given YAML content, e.g., an embedded
+/// `languages.yml`, and returns a list of [`Language`]. The ids and editor modes are optional,
+/// e.g., for custom definitions.
+///
+/// # Example
+/// ```
+/// use linguist::github::load_github_linguist_languages_from_str;
+///
+/// let content = r#"
+/// Rust:
+///   type: programming
+///   extensions: [".rs"]
+///   tm_scope: source.rust
+///   ace_mode: rust
+///   codemirror_mode: rust
+///   language_id: 327
+/// Custom:
+///   type: data
+///   extensions: [".custom"]
+/// "#;
+/// let languages = load_github_linguist_languages_from_str(content).unwrap();
+/// assert_eq!(languages[0].language_id, Some(327));
+/// assert_eq!(languages[0].tm_scope.as_deref(), Some("source.rust"));
+/// assert_eq!(languages[0].ace_mode.as_deref(), Some("rust"));
+/// assert_eq!(languages[1].language_id, None);
+/// assert_eq!(languages[1].codemirror_mode, None);
+/// ```
+pub fn load_github_linguist_languages_from_str(
+    content: &str,
+) -> Result<Vec<Language>, LinguistError> {
+    deserialize_languages_from_str::<GitHubLanguage>(content).map(mark_popular)
+}
+
+/// Loads all GitHub Linguist languages from the YAML files of the given directory and returns a
+/// list of [`Language`]. Languages defined in several files are overridden by the later file in
+/// alphabetical order.
+///
+/// # Example
+/// ```
+/// use linguist::github::load_github_linguist_languages_dir;
+///
+/// let languages = load_github_linguist_languages_dir("tests/fixtures/github/languages.d").unwrap();
+/// assert_eq!(languages.len(), 3);
+///
+/// let rust = languages.iter().find(|lang| lang.name == "Rust").unwrap();
+/// assert_eq!(rust.color.as_deref(), Some("#dea584"));
+/// ```
+pub fn load_github_linguist_languages_dir(
+    dir: impl AsRef<Path>,
+) -> Result<Vec<Language>, LinguistError> {
+    if !dir.as_ref().is_dir() {
+        return Err(LinguistError::FileNotFound);
+    }
+
+    deserialize_languages_dir::<GitHubLanguage>(dir).map(mark_popular)
+}
+
+/// Used internally to mark the popular languages once their names are known, as the name of a
+/// [`GitHubLanguage`] is the key of its definition and not part of it.
+fn mark_popular(mut languages: Vec<Language>) -> Vec<Language> {
+    for lang in languages.iter_mut() {
+        lang.popular = is_popular(&lang.name);
+    }
+    languages
+}
+
+/// Serializes the given [`Language`]s into YAML that is compatible with GitHub Linguist's
+/// `languages.yml`, i.e., it can be loaded again with [`load_github_linguist_languages`]. The
+/// languages are ordered by name; use [`serialize_languages`] to keep the given order.
+///
+/// # Example
+/// ```
+/// use linguist::github::{load_github_linguist_languages, to_github_yaml};
+///
+/// let mut languages = load_github_linguist_languages("tests/fixtures/github/languages.yml").unwrap();
+/// languages.sort_by(|a, b| a.name.cmp(&b.name));
+///
+/// let path = std::env::temp_dir().join("linguist-to-github-yaml.yml");
+/// std::fs::write(&path, to_github_yaml(&languages).unwrap()).unwrap();
+///
+/// let mut reloaded = load_github_linguist_languages(&path).unwrap();
+/// reloaded.sort_by(|a, b| a.name.cmp(&b.name));
+/// assert_eq!(languages, reloaded);
+/// ```
+pub fn to_github_yaml(languages: &[Language]) -> Result<String, LinguistError> {
+    let mut sorted = languages.to_vec();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+    serialize_languages(&sorted)
+}
+
+/// Serializes the given [`Language`]s into YAML that is keyed by the language name and uses the
+/// field names of GitHub Linguist's `languages.yml`, e.g., `type` and `group`, and extensions with
+/// their leading `.`. Unlike [`to_github_yaml`], the languages are kept in the given order, e.g.,
+/// to maintain a curated subset of the definitions. A language that is listed several times is
+/// written once, with its last definition.
+///
+/// # Example
+/// ```
+/// use linguist::github::{
+///     load_github_linguist_languages, load_github_linguist_languages_from_str, serialize_languages,
+/// };
+///
+/// let languages = load_github_linguist_languages("tests/fixtures/github/languages.yml").unwrap();
+/// let yaml = serialize_languages(&languages).unwrap();
+/// assert!(yaml.starts_with("C:\n  color: '#555555'\n  type: programming\n"));
+/// assert!(yaml.contains("- .rs\n"));
+///
+/// let reloaded = load_github_linguist_languages_from_str(&yaml).unwrap();
+/// assert_eq!(languages, reloaded);
+///
+/// let mut duplicated = languages.clone();
+/// duplicated[0].color = Some("#000000".to_string());
+/// duplicated.push(languages[0].clone());
+/// let reloaded = load_github_linguist_languages_from_str(&serialize_languages(&duplicated).unwrap());
+/// assert_eq!(reloaded.unwrap().len(), languages.len());
+/// ```
+pub fn serialize_languages(languages: &[Language]) -> Result<String, LinguistError> {
+    struct Ordered<'a>(&'a [Language]);
+
+    impl serde::Serialize for Ordered<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let last: HashMap<&str, usize> = self
+                .0
+                .iter()
+                .enumerate()
+                .map(|(index, lang)| (lang.name.as_str(), index))
+                .collect();
+            serializer.collect_map(
+                self.0
+                    .iter()
+                    .enumerate()
+                    .filter(|&(index, lang)| last[lang.name.as_str()] == index)
+                    .map(|(_, lang)| (lang.name.as_str(), GitHubLanguage::from(lang))),
+            )
+        }
+    }
+
+    serde_yaml::to_string(&Ordered(languages)).map_err(|_| LinguistError::SerializationError)
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -74,6 +299,7 @@ struct Rule {
     #[serde(rename = "and")]
     and_rules: Option<Vec<NamedPattern>>,
     pattern: Option<PatternValue>,
+    named_pattern: Option<PatternValue>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -103,83 +329,263 @@ impl Display for PatternValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PatternValue::Single(val) => write!(f, "{}", val),
-            PatternValue::Multiple(val) => write!(f, "{}", val.join("|")),
+            PatternValue::Multiple(val) => write!(f, "{}", combine_alternatives(val)),
         }
     }
 }
 
+/// Used internally to combine alternative patterns into a single pattern. Each alternative is
+/// wrapped into a non-capturing group, so that it cannot be merged with its neighbours.
+fn combine_alternatives(patterns: &[String]) -> String {
+    match patterns {
+        [pattern] => pattern.to_string(),
+        _ => patterns
+            .iter()
+            .map(|pattern| format!("(?:{})", pattern))
+            .collect::<Vec<String>>()
+            .join("|"),
+    }
+}
+
+/// Used internally to look up the patterns of the given named pattern(s) and combine them into a
+/// single pattern. Returns `None` if any of the names is not defined.
+fn resolve_named_pattern(
+    names: PatternValue,
+    named_patterns: &HashMap<String, PatternValue>,
+) -> Option<String> {
+    let names = match names {
+        PatternValue::Single(val) => vec![val],
+        PatternValue::Multiple(val) => val,
+    };
+
+    let mut alternatives: Vec<String> = Vec::new();
+    for name in names {
+        match named_patterns.get(&name)? {
+            PatternValue::Single(val) => alternatives.push(val.to_string()),
+            PatternValue::Multiple(val) => alternatives.extend(val.iter().cloned()),
+        }
+    }
+
+    if alternatives.is_empty() {
+        return None;
+    }
+
+    Some(combine_alternatives(&alternatives))
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct NamedPattern {
-    pattern: Option<String>,
+    pattern: Option<PatternValue>,
     named_pattern: Option<PatternValue>,
 }
 
 #[derive(Debug, serde::Deserialize)]
 struct YamlContent {
     disambiguations: Vec<Disambiguation>,
-    named_patterns: HashMap<String, RuleLanguage>,
+    /// Patterns that are referenced by name, i.e., a single pattern or a list of alternatives.
+    #[serde(default)]
+    named_patterns: HashMap<String, PatternValue>,
 }
 
 /// Loads all GitHub Linguist heuristics from the given file and returns list of [`HeuristicRule`].
+///
+/// # Example
+/// ```
+/// use linguist::github::load_github_linguist_heuristics;
+///
+/// let rules = load_github_linguist_heuristics("tests/fixtures/github/heuristics.yml").unwrap();
+/// let pascal = rules.iter().find(|rule| rule.language == "Pascal").unwrap();
+///
+/// // the `and` rule consists of two components, the second one being a multi-valued named pattern
+/// assert!(pascal.patterns.is_empty());
+/// assert_eq!(pascal.required_patterns.len(), 2);
+/// assert!(pascal.is_match("unit Shapes;\ninterface\nend.").unwrap());
+/// assert!(pascal.is_match("program Hello;\nbegin\nend.").unwrap());
+/// assert!(!pascal.is_match("unit Shapes;").unwrap());
+/// assert!(!pascal.is_match("end.").unwrap());
+/// ```
+///
+/// Extensions that are shared by several languages, like `.m`, `.t`, and `.v`, are decided by
+/// the content of the file:
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::github::load_github_linguist_heuristics;
+/// use linguist::resolver::{resolve_language, Language, Scope};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// let languages = [
+///     ("Objective-C", "m"), ("MATLAB", "m"), ("Mathematica", "m"), ("Mercury", "m"),
+///     ("Perl", "t"), ("Raku", "t"), ("Turing", "t"),
+///     ("Coq", "v"), ("Verilog", "v"), ("V", "v"),
+/// ];
+/// for (name, ext) in languages {
+///     container.register_language(Language {
+///         name: name.to_string(),
+///         scope: Scope::Programming,
+///         extensions: vec![ext.into()],
+///         ..Default::default()
+///     });
+/// }
+/// for rule in load_github_linguist_heuristics("tests/fixtures/github/heuristics.yml").unwrap() {
+///     container.register_heuristic_rule(rule);
+/// }
+///
+/// let resolve = |file: &str| {
+///     let path = format!("tests/fixtures/ambiguous/{}", file);
+///     resolve_language(path, &container).unwrap().unwrap().name.as_str()
+/// };
+/// assert_eq!(resolve("Foundation.m"), "Objective-C");
+/// assert_eq!(resolve("average.m"), "MATLAB");
+/// assert_eq!(resolve("square.m"), "Mathematica");
+/// assert_eq!(resolve("basic.t"), "Perl");
+/// assert_eq!(resolve("plan.t"), "Raku");
+/// assert_eq!(resolve("counter.t"), "Turing");
+/// assert_eq!(resolve("plus.v"), "Coq");
+/// assert_eq!(resolve("counter.v"), "Verilog");
+/// assert_eq!(resolve("hello.v"), "V");
+/// ```
+///
+/// Named patterns may be a single pattern or a list of alternatives, as for the `.h` rules of
+/// C, C++, and Objective-C:
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::github::load_github_linguist_heuristics;
+/// use linguist::resolver::{resolve_language, Language, Scope};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// for name in ["C", "C++", "Objective-C"] {
+///     container.register_language(Language {
+///         name: name.to_string(),
+///         scope: Scope::Programming,
+///         extensions: vec!["h".into()],
+///         ..Default::default()
+///     });
+/// }
+/// for rule in load_github_linguist_heuristics("tests/fixtures/github/heuristics-h.yml").unwrap() {
+///     container.register_heuristic_rule(rule);
+/// }
+///
+/// let resolve = |file: &str| {
+///     let path = format!("tests/fixtures/{}", file);
+///     resolve_language(path, &container).unwrap().unwrap().name.as_str()
+/// };
+/// assert_eq!(resolve("heuristics/view.h"), "Objective-C");
+/// assert_eq!(resolve("heuristics/vector.h"), "C++");
+/// assert_eq!(resolve("ambiguous/header.h"), "C");
+/// ```
 #[cfg(feature = "matcher")]
 pub fn load_github_linguist_heuristics(
     path: impl AsRef<Path>,
 ) -> Result<Vec<HeuristicRule>, LinguistError> {
     let content = std::fs::read_to_string(path)?;
-    let data = serde_yaml::from_str::<YamlContent>(&content);
+    load_github_linguist_heuristics_from_str(&content)
+}
+
+/// Loads all GitHub Linguist heuristics from the given YAML content, e.g., an embedded
+/// `heuristics.yml`, see [`load_github_linguist_heuristics`]. Content that is not a valid
+/// heuristics file is returned as [`LinguistError::DeserializationError`], and rules that
+/// reference an undefined named pattern are skipped.
+///
+/// # Example
+/// ```
+/// use linguist::error::LinguistError;
+/// use linguist::github::load_github_linguist_heuristics_from_str;
+///
+/// let content = r#"
+/// disambiguations:
+/// - extensions: ['.h']
+///   rules:
+///   - language: Objective-C
+///     named_pattern: objectivec
+///   - language: C
+/// "#;
+/// let rules = load_github_linguist_heuristics_from_str(content).unwrap();
+/// assert_eq!(rules.len(), 1);
+/// assert_eq!(rules[0].language, "C");
+///
+/// let rules = load_github_linguist_heuristics_from_str("<html></html>");
+/// assert!(matches!(rules, Err(LinguistError::DeserializationError)));
+/// ```
+///
+/// A rule that lists several languages results in one rule per language:
+/// ```
+/// use linguist::github::load_github_linguist_heuristics_from_str;
+///
+/// let content = r#"
+/// disambiguations:
+/// - extensions: ['.pl']
+///   rules:
+///   - language: [Perl, Prolog]
+///     pattern: '^\s*use\s+strict'
+/// "#;
+/// let rules = load_github_linguist_heuristics_from_str(content).unwrap();
+/// let languages: Vec<&str> = rules.iter().map(|rule| rule.language.as_str()).collect();
+/// assert_eq!(languages, ["Perl", "Prolog"]);
+/// assert!(rules.iter().all(|rule| rule.patterns == [r"^\s*use\s+strict"]));
+/// ```
+#[cfg(feature = "matcher")]
+pub fn load_github_linguist_heuristics_from_str(
+    content: &str,
+) -> Result<Vec<HeuristicRule>, LinguistError> {
+    let data = serde_yaml::from_str::<YamlContent>(content)
+        .map_err(|_| LinguistError::DeserializationError)?;
 
     let mut rules: Vec<HeuristicRule> = Vec::new();
-    if let Ok(data) = data {
-        for disambiguation in data.disambiguations {
-            for rule in disambiguation.rules {
-                let lang = match rule.language {
-                    RuleLanguage::Single(val) => val,
-                    // TODO(multiple names): we should consider the case when more than
-                    // one name is available to reference a certain rule as well...
-                    _ => "".to_string(),
-                };
-
-                let mut heuristic_rule = HeuristicRule {
-                    language: lang,
-                    extensions: disambiguation
-                        .extensions
-                        .iter()
-                        // because `Path.extension()` requires that an extension does not begin with `.`,
-                        // we remove the first `.` from the extension
-                        .map(|ext| OsString::from(ext.replacen('.', "", 1)))
-                        .collect(),
-                    patterns: vec![],
-                };
-
-                if let Some(pattern) = rule.pattern {
-                    heuristic_rule.patterns.push(pattern.to_string());
+    for disambiguation in data.disambiguations {
+        for rule in disambiguation.rules {
+            let languages = match rule.language {
+                RuleLanguage::Single(val) => vec![val],
+                RuleLanguage::Multiple(val) => val,
+            };
+
+            let mut heuristic_rule = HeuristicRule::new(
+                String::new(),
+                disambiguation
+                    .extensions
+                    .iter()
+                    .map(|ext| normalize_extension(ext))
+                    .collect(),
+                vec![],
+            );
+
+            if let Some(pattern) = rule.pattern {
+                heuristic_rule.patterns.push(pattern.to_string());
+            }
+
+            // a rule that references an undefined named pattern is skipped, as dropping the
+            // reference would make the rule match more than intended
+            let mut resolved = true;
+            if let Some(pattern) = rule.named_pattern {
+                match resolve_named_pattern(pattern, &data.named_patterns) {
+                    Some(pattern) => heuristic_rule.patterns.push(pattern),
+                    None => resolved = false,
                 }
+            }
 
-                if let Some(refs) = rule.and_rules {
-                    for np_ref in refs {
-                        if let Some(pattern) = np_ref.pattern {
-                            heuristic_rule.patterns.push(pattern.to_string());
-                        }
+            // every entry of an `and` rule is a separate component that has to match; the
+            // alternatives of an entry are combined into a single pattern
+            if let Some(refs) = rule.and_rules {
+                for np_ref in refs {
+                    if let Some(pattern) = np_ref.pattern {
+                        heuristic_rule.required_patterns.push(pattern.to_string());
+                    }
 
-                        if let Some(pattern) = np_ref.named_pattern {
-                            match pattern {
-                                PatternValue::Single(val) => {
-                                    if let Some(p_ref) = data.named_patterns.get(&val) {
-                                        heuristic_rule.patterns.push(p_ref.to_string());
-                                    }
-                                }
-                                PatternValue::Multiple(val) => {
-                                    for val in val {
-                                        if let Some(p_ref) = data.named_patterns.get(&val) {
-                                            heuristic_rule.patterns.push(p_ref.to_string());
-                                        }
-                                    }
-                                }
-                            }
+                    if let Some(pattern) = np_ref.named_pattern {
+                        match resolve_named_pattern(pattern, &data.named_patterns) {
+                            Some(pattern) => heuristic_rule.required_patterns.push(pattern),
+                            None => resolved = false,
                         }
                     }
                 }
-                rules.push(heuristic_rule);
+            }
+
+            // a rule that lists several languages applies to each of them
+            if resolved {
+                for language in languages {
+                    let mut rule = heuristic_rule.clone();
+                    rule.language = language;
+                    rules.push(rule);
+                }
             }
         }
     }
@@ -187,30 +593,209 @@ pub fn load_github_linguist_heuristics(
     Ok(rules)
 }
 
-/// Loads all GitHub Linguist vendors from the given file and returns list of strings.
-pub fn load_github_vendors(path: impl AsRef<Path>) -> Result<Vec<String>, LinguistError> {
-    let content = deserialize_strings(path)?;
+/// `LoadedPatterns` contain the patterns of a definition file that can be used by the
+/// [regex crate](https://crates.io/crates/regex), and the rejected ones together with the reason,
+/// i.e., [`LinguistError::UnsupportedPattern`] or [`LinguistError::PatternCompileError`].
+#[derive(Debug, Default)]
+pub struct LoadedPatterns {
+    pub patterns: Vec<String>,
+    pub rejected: Vec<LinguistError>,
+}
 
-    let mut data: Vec<String> = Vec::new();
+/// Used internally to split the given patterns into usable and rejected ones.
+fn check_patterns(content: Vec<String>) -> LoadedPatterns {
+    let mut data = LoadedPatterns::default();
     for rule in content {
-        if !is_unsupported_regex_syntax(rule.as_str()) {
-            data.push(rule.to_string());
+        match check_pattern(rule.as_str()) {
+            Ok(()) => data.patterns.push(rule),
+            Err(err) => data.rejected.push(err),
         }
     }
 
-    Ok(data)
+    data
+}
+
+/// Loads all GitHub Linguist vendors from the given file and returns list of strings. Patterns
+/// that cannot be used are dropped; use [`load_github_vendors_checked`] to retrieve them.
+/// A file that cannot be read or is not a YAML list of strings, e.g., an HTML error page that was
+/// served instead of `vendor.yml`, is returned as an error.
+///
+/// # Example
+/// ```
+/// use linguist::error::LinguistError;
+/// use linguist::github::{load_github_vendors, load_github_vendors_from_str};
+///
+/// let vendors = load_github_vendors_from_str("<html><body>502 Bad Gateway</body></html>");
+/// assert!(matches!(vendors, Err(LinguistError::DeserializationError)));
+///
+/// let vendors = load_github_vendors("tests/fixtures/github/missing.yml");
+/// assert!(matches!(vendors, Err(LinguistError::IOError(_))));
+/// ```
+pub fn load_github_vendors(path: impl AsRef<Path>) -> Result<Vec<String>, LinguistError> {
+    Ok(load_github_vendors_checked(path)?.patterns)
+}
+
+/// Loads all GitHub Linguist vendors from the given file and returns the usable and the rejected
+/// patterns.
+///
+/// # Example
+/// ```
+/// use linguist::error::LinguistError;
+/// use linguist::github::load_github_vendors_checked;
+///
+/// let vendors = load_github_vendors_checked("tests/fixtures/github/vendor.yml").unwrap();
+/// assert_eq!(vendors.patterns, vec!["(^|/)vendor/", "(^|/)node_modules/"]);
+/// assert!(matches!(
+///     &vendors.rejected[..],
+///     [LinguistError::UnsupportedPattern(pattern)] if pattern == "(?<!bower_)components/"
+/// ));
+/// ```
+pub fn load_github_vendors_checked(
+    path: impl AsRef<Path>,
+) -> Result<LoadedPatterns, LinguistError> {
+    load_github_vendors_checked_from_str(&std::fs::read_to_string(path)?)
+}
+
+/// Loads all GitHub Linguist vendors from the given YAML content, e.g., an embedded `vendor.yml`.
+/// Patterns that cannot be used are dropped.
+pub fn load_github_vendors_from_str(content: &str) -> Result<Vec<String>, LinguistError> {
+    Ok(load_github_vendors_checked_from_str(content)?.patterns)
+}
+
+/// Loads all GitHub Linguist vendors from the given YAML content and returns the usable and the
+/// rejected patterns, see [`load_github_vendors_checked`].
+pub fn load_github_vendors_checked_from_str(
+    content: &str,
+) -> Result<LoadedPatterns, LinguistError> {
+    Ok(check_patterns(deserialize_strings_from_str(content)?))
 }
 
 /// Loads all GitHub Linguist documentation from the given file and returns list of strings.
+/// Patterns that cannot be used are dropped; use [`load_github_documentation_checked`] to retrieve
+/// them.
 pub fn load_github_documentation(path: impl AsRef<Path>) -> Result<Vec<String>, LinguistError> {
-    let content = deserialize_strings(path)?;
+    Ok(load_github_documentation_checked(path)?.patterns)
+}
 
-    let mut data: Vec<String> = Vec::new();
-    for rule in content {
-        if !is_unsupported_regex_syntax(rule.as_str()) {
-            data.push(rule.to_string());
+/// Loads all GitHub Linguist documentation from the given file and returns the usable and the
+/// rejected patterns.
+pub fn load_github_documentation_checked(
+    path: impl AsRef<Path>,
+) -> Result<LoadedPatterns, LinguistError> {
+    load_github_documentation_checked_from_str(&std::fs::read_to_string(path)?)
+}
+
+/// Loads all GitHub Linguist documentation from the given YAML content, e.g., an embedded
+/// `documentation.yml`. Patterns that cannot be used are dropped.
+///
+/// # Example
+/// ```
+/// use linguist::github::load_github_documentation_from_str;
+///
+/// static DOCUMENTATION: &str = include_str!("../tests/fixtures/github/documentation.yml");
+///
+/// let documentation = load_github_documentation_from_str(DOCUMENTATION).unwrap();
+/// assert!(!documentation.is_empty());
+/// let documentation = load_github_documentation_from_str("- ^docs?/\n- (^|/)CHANGELOG").unwrap();
+/// assert_eq!(documentation, ["^docs?/", "(^|/)CHANGELOG"]);
+/// ```
+pub fn load_github_documentation_from_str(content: &str) -> Result<Vec<String>, LinguistError> {
+    Ok(load_github_documentation_checked_from_str(content)?.patterns)
+}
+
+/// Loads all GitHub Linguist documentation from the given YAML content and returns the usable and
+/// the rejected patterns, see [`load_github_documentation_checked`].
+pub fn load_github_documentation_checked_from_str(
+    content: &str,
+) -> Result<LoadedPatterns, LinguistError> {
+    Ok(check_patterns(deserialize_strings_from_str(content)?))
+}
+
+/// Used internally to interpret embedded definitions as UTF-8.
+fn embedded_str(bytes: &[u8]) -> Result<&str, LinguistError> {
+    std::str::from_utf8(bytes).map_err(|_| LinguistError::DeserializationError)
+}
+
+#[cfg(feature = "matcher")]
+impl InMemoryLanguageContainer {
+    /// Creates a container from embedded GitHub Linguist definitions, i.e., the content of
+    /// `languages.yml` and `heuristics.yml`, without any disk access. Vendor and documentation
+    /// rules are not part of a container; they are loaded with [`Filters::from_embedded_bytes`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::classifier::Filters;
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::resolve_language;
+    ///
+    /// static LANGUAGES: &[u8] = include_bytes!("../tests/fixtures/github/languages.yml");
+    /// static HEURISTICS: &[u8] = include_bytes!("../tests/fixtures/github/heuristics.yml");
+    /// static VENDORS: &[u8] = include_bytes!("../tests/fixtures/github/vendor.yml");
+    /// static DOCUMENTATION: &[u8] = include_bytes!("../tests/fixtures/github/documentation.yml");
+    ///
+    /// let container = InMemoryLanguageContainer::from_embedded_bytes(LANGUAGES, HEURISTICS).unwrap();
+    /// let lang = resolve_language("tests/fixtures/ambiguous/Foundation.m", &container).unwrap();
+    /// assert_eq!(lang.unwrap().name, "Objective-C");
+    /// assert_eq!(container.get_language_by_id(327).unwrap().name, "Rust");
+    ///
+    /// let filters = Filters::from_embedded_bytes(VENDORS, DOCUMENTATION).unwrap();
+    /// assert!(filters.check("node_modules/left-pad/index.js").is_some());
+    /// assert!(filters.check("docs/index.md").is_some());
+    /// assert!(filters.check("src/main.rs").is_none());
+    /// ```
+    pub fn from_embedded_bytes(languages: &[u8], heuristics: &[u8]) -> Result<Self, LinguistError> {
+        let mut container = InMemoryLanguageContainer::from_iter(
+            load_github_linguist_languages_from_str(embedded_str(languages)?)?,
+        );
+        for rule in load_github_linguist_heuristics_from_str(embedded_str(heuristics)?)? {
+            container.register_heuristic_rule(rule);
         }
+
+        Ok(container)
+    }
+}
+
+impl Filters {
+    /// Creates the default [`Filters`] with the vendor and documentation rules of embedded GitHub
+    /// Linguist definitions, i.e., the content of `vendor.yml` and `documentation.yml`, see
+    /// [`InMemoryLanguageContainer::from_embedded_bytes`]. Patterns that cannot be used are
+    /// dropped.
+    pub fn from_embedded_bytes(
+        vendors: &[u8],
+        documentation: &[u8],
+    ) -> Result<Self, LinguistError> {
+        let vendors = load_github_vendors_from_str(embedded_str(vendors)?)?;
+        let documentation = load_github_documentation_from_str(embedded_str(documentation)?)?;
+
+        Ok(Filters::new()
+            .vendors(RegexSet::new(vendors)?)
+            .documentation(RegexSet::new(documentation)?))
     }
+}
+
+/// Loads a mapping of language names to reference URLs from the given file and returns a list of
+/// `(name, url)` pairs ordered by name.
+pub fn load_language_urls(path: impl AsRef<Path>) -> Result<Vec<(String, String)>, LinguistError> {
+    let content = std::fs::read_to_string(path)?;
+    load_language_urls_from_str(&content)
+}
+
+/// Loads a mapping of language names to reference URLs from the given YAML content, see
+/// [`load_language_urls`].
+///
+/// # Example
+/// ```
+/// use linguist::github::load_language_urls_from_str;
+///
+/// let content = "Rust: https://www.rust-lang.org\nGo: https://go.dev";
+/// let urls = load_language_urls_from_str(content).unwrap();
+/// assert_eq!(urls[0], ("Go".to_string(), "https://go.dev".to_string()));
+/// ```
+pub fn load_language_urls_from_str(content: &str) -> Result<Vec<(String, String)>, LinguistError> {
+    let mut data: Vec<(String, String)> = deserialize_string_map_from_str(content)?
+        .into_iter()
+        .collect();
+    data.sort();
 
     Ok(data)
 }
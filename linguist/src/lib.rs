@@ -1,8 +1,18 @@
+#[cfg(feature = "bundled")]
+pub mod bundled;
+pub mod classifier;
 pub mod container;
 pub mod error;
+pub mod gitattributes;
 #[cfg(feature = "github-linguist-yaml")]
 pub mod github;
 pub mod resolver;
 #[cfg(feature = "serde")]
 pub mod serde;
+pub mod stats;
+#[cfg(feature = "tokio")]
+pub mod tokio;
 pub mod utils;
+
+#[cfg(feature = "bundled")]
+pub use bundled::{detect_bytes, detect_path};
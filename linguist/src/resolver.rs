@@ -1,18 +1,27 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fmt::Display;
-use std::io::{BufRead, BufReader};
+use std::io::Read;
 use std::path::Path;
+use std::sync::Arc;
+#[cfg(feature = "matcher")]
+use std::sync::OnceLock;
 
 #[cfg(feature = "matcher")]
-use regex::Regex;
+use regex::{RegexSet, RegexSetBuilder};
 
 use crate::container::Container;
 use crate::error::LinguistError;
-use crate::utils::{determine_multiline_exec, has_shebang, is_binary};
+use crate::gitattributes::GitAttributes;
+use crate::utils::{
+    decode_text, detect_magic, is_binary, is_binary_bytes, is_template,
+    is_unsupported_regex_syntax, parse_modeline, parse_shebang, FIRST_FEW_BYTES,
+};
 
 /// A `Language` exposes the properties of a language definition.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Language {
     pub parent: Option<String>,
@@ -21,8 +30,24 @@ pub struct Language {
     pub scope: Scope,
     pub extensions: Vec<OsString>,
     pub filenames: Vec<OsString>,
+    /// Glob patterns of filenames that identify the language, e.g., `Dockerfile.*`, see
+    /// [`glob_match`](crate::utils::glob_match). They are only matched if no filename matches.
+    pub filename_patterns: Vec<String>,
     pub interpreters: Vec<String>,
     pub color: Option<String>,
+    /// The numeric id that GitHub's Linguist assigns to the language, e.g., `327` for Rust.
+    pub language_id: Option<u64>,
+    /// The TextMate scope that is used for syntax highlighting, e.g., `source.rust`.
+    pub tm_scope: Option<String>,
+    /// The mode of the Ace editor, e.g., `rust`.
+    pub ace_mode: Option<String>,
+    /// The mode of the CodeMirror editor, e.g., `rust`.
+    pub codemirror_mode: Option<String>,
+    /// Whether the language is popular, e.g., one of the
+    /// [`POPULAR_LANGUAGES`](crate::utils::POPULAR_LANGUAGES). With the default
+    /// [`TieBreak::PreferPopular`], a popular language wins over other candidates with the same
+    /// score, e.g., of an ambiguous extension.
+    pub popular: bool,
 }
 
 impl Display for Language {
@@ -31,37 +56,146 @@ impl Display for Language {
     }
 }
 
-/// A `Scope` represents the type of a [`Language`]. 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Language {
+    /// Creates a new `Language` with the given name. All other properties are empty and can be set
+    /// with the builder methods, e.g., to define a small container inline, see
+    /// [`InMemoryLanguageContainer`](crate::container::InMemoryLanguageContainer).
+    pub fn new(name: impl Into<String>) -> Self {
+        Language {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the scope of the language.
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Sets the aliases of the language.
+    pub fn aliases<S: Into<String>>(mut self, aliases: impl IntoIterator<Item = S>) -> Self {
+        self.aliases = aliases.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the extensions of the language, without the leading dot, e.g., `rs`.
+    pub fn extensions<S: Into<OsString>>(
+        mut self,
+        extensions: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the exact filenames of the language, e.g., `Makefile`.
+    pub fn filenames<S: Into<OsString>>(mut self, filenames: impl IntoIterator<Item = S>) -> Self {
+        self.filenames = filenames.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the glob patterns of filenames of the language, see [`Language::filename_patterns`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::{Container, InMemoryLanguageContainer};
+    /// use linguist::resolver::Language;
+    ///
+    /// let container = InMemoryLanguageContainer::from_languages([
+    ///     Language::new("Dockerfile")
+    ///         .filenames(["Dockerfile"])
+    ///         .filename_patterns(["Dockerfile.*", "*.dockerfile"]),
+    ///     Language::new("Groovy").filename_patterns(["Jenkinsfile*"]),
+    /// ]);
+    ///
+    /// let name = |file| container.get_languages_by_filename(file).map(|langs| langs[0].name.as_str());
+    /// assert_eq!(name("Dockerfile.dev"), Some("Dockerfile"));
+    /// assert_eq!(name("api.dockerfile"), Some("Dockerfile"));
+    /// assert_eq!(name("Jenkinsfile.release"), Some("Groovy"));
+    /// assert_eq!(name("Makefile"), None);
+    /// ```
+    pub fn filename_patterns<S: Into<String>>(
+        mut self,
+        patterns: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.filename_patterns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets whether the language is popular, see [`Language::popular`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language, Language};
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("MATLAB").extensions(["m"]),
+    ///     Language::new("Objective-C").extensions(["m"]).popular(true),
+    ///     Language::new("Mathematica").extensions(["m"]),
+    /// ]);
+    ///
+    /// // all candidates share the extension, so the popular one wins
+    /// let file = "tests/fixtures/ambiguous/average.m";
+    /// let lang = resolve_language(file, &container).unwrap();
+    /// assert_eq!(lang.unwrap().name, "Objective-C");
+    /// ```
+    pub fn popular(mut self, popular: bool) -> Self {
+        self.popular = popular;
+        self
+    }
+
+    /// Sets the interpreters of the language, e.g., `python3`.
+    pub fn interpreters<S: Into<String>>(
+        mut self,
+        interpreters: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.interpreters = interpreters.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// A `Scope` represents the type of a [`Language`]. A type that is not known to this crate is
+/// kept as [`Scope::Other`], while [`Scope::Unknown`] means that no type was given at all.
+///
+/// # Example
+/// ```
+/// use linguist::resolver::Scope;
+///
+/// assert_eq!(Scope::from("Programming"), Scope::Programming);
+/// assert_eq!(Scope::from(""), Scope::Unknown);
+/// assert_eq!(Scope::from("config"), Scope::Other("config".to_string()));
+/// assert_eq!(Scope::from("config").to_string(), "config");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Scope {
     Programming,
     Markup,
     Data,
     Prose,
+    /// A type that is not known to this crate, as it was given by the definition.
+    Other(String),
+    #[default]
     Unknown,
 }
 
 impl From<String> for Scope {
     fn from(value: String) -> Self {
-        match value.to_lowercase().as_str() {
-            "programming" => Scope::Programming,
-            "markup" => Scope::Markup,
-            "data" => Scope::Data,
-            "prose" => Scope::Prose,
-            _ => Scope::Unknown,
-        }
+        Scope::from(value.as_str())
     }
 }
 
 impl From<&str> for Scope {
     fn from(value: &str) -> Self {
-        match value.to_lowercase().as_str() {
+        match value.to_ascii_lowercase().as_str() {
             "programming" => Scope::Programming,
             "markup" => Scope::Markup,
             "data" => Scope::Data,
             "prose" => Scope::Prose,
-            _ => Scope::Unknown,
+            // `Unknown` is written by `Display`, so it is read back as such
+            "" | "unknown" => Scope::Unknown,
+            _ => Scope::Other(value.to_string()),
         }
     }
 }
@@ -73,6 +207,7 @@ impl std::fmt::Display for Scope {
             Scope::Markup => write!(f, "Markup"),
             Scope::Data => write!(f, "Data"),
             Scope::Prose => write!(f, "Prose"),
+            Scope::Other(value) => write!(f, "{}", value),
             Scope::Unknown => write!(f, "Unknown"),
         }
     }
@@ -86,11 +221,253 @@ pub struct HeuristicRule {
     pub language: String,
     /// A list of extensions that are used to check whether this rule applies.
     pub extensions: Vec<OsString>,
-    /// A list of patterns that are used to check whether this rule applies.
+    /// A list of patterns that are used to check whether this rule applies. The rule applies if
+    /// any of the patterns matches.
     pub patterns: Vec<String>,
+    /// A list of patterns that all have to match for this rule to apply, in addition to one of the
+    /// `patterns`, if there are any. They are the components of an `and` rule of GitHub's
+    /// Linguist, see [`HeuristicRule::all_of`].
+    #[cfg_attr(feature = "matcher", serde(default))]
+    pub required_patterns: Vec<String>,
+    /// The compiled patterns, see [`HeuristicRule::matcher`].
+    #[cfg(feature = "matcher")]
+    #[cfg_attr(feature = "matcher", serde(skip))]
+    matcher: LazyMatcher,
 }
 
-/// Used to resolve all possible [`Language`]s by the given filename. 
+impl HeuristicRule {
+    /// Creates a new `HeuristicRule` for the given language.
+    pub fn new(language: String, extensions: Vec<OsString>, patterns: Vec<String>) -> Self {
+        HeuristicRule {
+            language,
+            extensions,
+            patterns,
+            required_patterns: vec![],
+            #[cfg(feature = "matcher")]
+            matcher: LazyMatcher::default(),
+        }
+    }
+
+    /// Creates a new `HeuristicRule` for the given language that only applies if all of the given
+    /// patterns match, like an `and` rule of GitHub's Linguist.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::resolver::HeuristicRule;
+    ///
+    /// let rule = HeuristicRule::all_of(
+    ///     "Pascal".to_string(),
+    ///     vec!["pp".into()],
+    ///     vec![r"^\s*end[.;]".to_string(), r"^\s*(unit|program)\s".to_string()],
+    /// );
+    /// assert!(rule.is_match("program Hello;\nbegin\nend.").unwrap());
+    /// assert!(!rule.is_match("end.").unwrap());
+    ///
+    /// let rule = HeuristicRule::new(rule.language, rule.extensions, rule.required_patterns);
+    /// assert!(rule.is_match("end.").unwrap());
+    /// ```
+    pub fn all_of(
+        language: String,
+        extensions: Vec<OsString>,
+        required_patterns: Vec<String>,
+    ) -> Self {
+        HeuristicRule {
+            required_patterns,
+            ..HeuristicRule::new(language, extensions, vec![])
+        }
+    }
+
+    /// Returns the compiled patterns of this rule, i.e., the `patterns` followed by the
+    /// `required_patterns`. The patterns are compiled once on the first call; subsequent calls,
+    /// also from other threads, reuse the compiled [`RegexSet`]. Like in GitHub's Linguist, `^`
+    /// and `$` match at the beginning and end of each line.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for name in ["Coq", "Verilog"] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         extensions: vec!["v".into()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "Verilog".to_string(),
+    ///     vec!["v".into()],
+    ///     vec![r"^\s*module\b".to_string()],
+    /// ));
+    ///
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..4 {
+    ///         scope.spawn(|| {
+    ///             let lang = resolve_language("tests/fixtures/ambiguous/counter.v", &container);
+    ///             assert_eq!(lang.unwrap().unwrap().name, "Verilog");
+    ///         });
+    ///     }
+    /// });
+    /// ```
+    #[cfg(feature = "matcher")]
+    pub fn matcher(&self) -> Result<&RegexSet, LinguistError> {
+        match self.matcher.0.get_or_init(|| {
+            RegexSetBuilder::new(self.patterns.iter().chain(&self.required_patterns))
+                .multi_line(true)
+                .build()
+        }) {
+            Ok(matcher) => Ok(matcher),
+            // name the offending pattern if it uses an unsupported construct
+            Err(err) => match self
+                .patterns
+                .iter()
+                .chain(&self.required_patterns)
+                .find(|pattern| is_unsupported_regex_syntax(pattern))
+            {
+                Some(pattern) => Err(LinguistError::UnsupportedPattern(pattern.to_string())),
+                None => Err(LinguistError::PatternCompileError(err.clone())),
+            },
+        }
+    }
+
+    /// Checks whether this rule applies to the given content, i.e., whether any of its `patterns`
+    /// and all of its `required_patterns` match. A rule without any patterns always applies.
+    #[cfg(feature = "matcher")]
+    pub fn is_match(&self, content: &str) -> Result<bool, LinguistError> {
+        let matches = self.matcher()?.matches(content);
+        let mut alternatives = 0..self.patterns.len();
+        let mut required = self.patterns.len()..matches.len();
+
+        Ok(
+            (alternatives.is_empty() || alternatives.any(|index| matches.matched(index)))
+                && required.all(|index| matches.matched(index)),
+        )
+    }
+
+    /// Used internally to determine how specific this rule is, i.e., the number of components
+    /// that have to match, see [`most_specific_match`].
+    #[cfg(feature = "matcher")]
+    fn specificity(&self) -> usize {
+        self.required_patterns.len() + usize::from(!self.patterns.is_empty())
+    }
+}
+
+/// A `ContentClassifier` resolves the [`Language`] of a file by its content, e.g., with a
+/// statistical model that is trained on code samples, see
+/// [`ResolveOptions::content_classifier`].
+///
+/// The classifier receives the beginning of the file, at most
+/// [`ResolveOptions::max_content_bytes`], and the candidates that are known from the other signals,
+/// i.e., the filename, shebang, and extension, ordered by their weight. It has to pick one of the
+/// candidates or return `None` if the content does not decide between them. It is not consulted
+/// if there are no candidates, or if the first line carries a marker, see
+/// [`Container::get_language_by_first_line`].
+pub trait ContentClassifier: std::fmt::Debug + Send + Sync {
+    /// Picks the [`Language`] of the given content out of the given candidates.
+    fn classify<'a>(&self, content: &[u8], candidates: &[&'a Language]) -> Option<&'a Language>;
+}
+
+/// A `HeuristicClassifier` is a [`ContentClassifier`] that applies [`HeuristicRule`]s in order
+/// and picks the candidate of the most specific matching rule, like
+/// [`resolve_language_by_content`]. Rules whose patterns cannot be compiled never match.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+///
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{
+///     resolve_language_by_content_with, HeuristicClassifier, HeuristicRule, Language,
+///     ResolveOptions,
+/// };
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// for name in ["MATLAB", "Objective-C"] {
+///     container.register_language(Language {
+///         name: name.to_string(),
+///         extensions: vec!["m".into()],
+///         ..Default::default()
+///     });
+/// }
+///
+/// let classifier = HeuristicClassifier::new(vec![HeuristicRule::new(
+///     "Objective-C".to_string(),
+///     vec!["m".into()],
+///     vec![r"^\s*@interface\b".to_string()],
+/// )]);
+/// let options = ResolveOptions {
+///     content_classifier: Some(Arc::new(classifier)),
+///     ..Default::default()
+/// };
+///
+/// let file = "tests/fixtures/ambiguous/Foundation.m";
+/// let lang = resolve_language_by_content_with(file, &container, &options).unwrap();
+/// assert_eq!(lang.unwrap().name, "Objective-C");
+/// ```
+#[cfg(feature = "matcher")]
+#[derive(Debug, Clone, Default)]
+pub struct HeuristicClassifier {
+    rules: Vec<HeuristicRule>,
+}
+
+#[cfg(feature = "matcher")]
+impl HeuristicClassifier {
+    /// Creates a new `HeuristicClassifier` that applies the given rules in order.
+    pub fn new(rules: Vec<HeuristicRule>) -> Self {
+        HeuristicClassifier { rules }
+    }
+}
+
+#[cfg(feature = "matcher")]
+impl ContentClassifier for HeuristicClassifier {
+    fn classify<'a>(&self, content: &[u8], candidates: &[&'a Language]) -> Option<&'a Language> {
+        let content = String::from_utf8_lossy(content);
+
+        let candidate = |rule: &HeuristicRule| {
+            // like `Container::get_language_by_name`, a name wins over an alias
+            candidates
+                .iter()
+                .copied()
+                .find(|lang| lang.name.eq_ignore_ascii_case(&rule.language))
+                .or_else(|| {
+                    candidates.iter().copied().find(|lang| {
+                        lang.aliases
+                            .iter()
+                            .any(|alias| alias.eq_ignore_ascii_case(&rule.language))
+                    })
+                })
+        };
+
+        let rules = self
+            .rules
+            .iter()
+            .filter(|rule| candidate(rule).is_some() && rule.matcher().is_ok());
+        most_specific_match(rules, &content)
+            .ok()
+            .flatten()
+            .and_then(candidate)
+    }
+}
+
+/// Used internally to compile the patterns of a [`HeuristicRule`] lazily and only once, even if the
+/// rule is shared across threads. Two matchers are always equal, since they are derived from the
+/// patterns of their rule.
+#[cfg(feature = "matcher")]
+#[derive(Debug, Clone, Default)]
+struct LazyMatcher(OnceLock<Result<RegexSet, regex::Error>>);
+
+#[cfg(feature = "matcher")]
+impl PartialEq for LazyMatcher {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "matcher")]
+impl Eq for LazyMatcher {}
+
+/// Used to resolve all possible [`Language`]s by the given filename.
 pub fn resolve_languages_by_filename(
     file: impl AsRef<Path>,
     container: &impl Container,
@@ -112,161 +489,1673 @@ pub fn resolve_languages_by_extension(
     }
 }
 
-/// Used to resolve all possible [`Language`]s by the file contents.
+/// Resolves the template [`Language`] of a template file, see [`is_template`], together with the
+/// underlying language it wraps. The template language is resolved by the outer extension, and the
+/// underlying language by the inner one, e.g., `index.html.erb` is ERB over HTML. Returns `None`
+/// if the file is not a template or its template language is unknown.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_template, Language};
+///
+/// let container = InMemoryLanguageContainer::from_iter([
+///     Language::new("HTML+ERB").extensions(["erb"]),
+///     Language::new("EJS").extensions(["ejs"]),
+///     Language::new("HTML").extensions(["html"]),
+///     Language::new("JavaScript").extensions(["js"]),
+/// ]);
+///
+/// let names = |file| {
+///     resolve_template(file, &container)
+///         .map(|(template, underlying)| (template.name.as_str(), underlying.map(|lang| lang.name.as_str())))
+/// };
+/// assert_eq!(names("views/index.html.erb"), Some(("HTML+ERB", Some("HTML"))));
+/// assert_eq!(names("views/app.js.ejs"), Some(("EJS", Some("JavaScript"))));
+/// assert_eq!(names("views/layout.erb"), Some(("HTML+ERB", None)));
+/// assert_eq!(names("src/app.js"), None);
+/// ```
+pub fn resolve_template(
+    file: impl AsRef<Path>,
+    container: &impl Container,
+) -> Option<(&Language, Option<&Language>)> {
+    let file = file.as_ref();
+    if !is_template(file) {
+        return None;
+    }
+
+    let template = container.languages_by_extension_iter(file).next()?;
+    let underlying = file.file_stem().and_then(|stem| {
+        container
+            .languages_by_extension_iter(Path::new(stem))
+            .next()
+    });
+
+    Some((template, underlying))
+}
+
+/// Used to resolve all possible [`Language`]s by the file contents. A marker at the beginning of the
+/// first line, see [`Container::get_language_by_first_line`], takes precedence over the heuristic
+/// rules. Only the first [`DEFAULT_MAX_CONTENT_BYTES`] of the file are considered. A rule can
+/// reference its language by name or by alias, see [`Container::get_language_by_name`]. Each rule
+/// is evaluated on its own; if several rules match, the one with the most components that have
+/// to match wins, see [`HeuristicRule::all_of`], and ties are decided by the order of the rules.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language, resolve_language_by_content, HeuristicRule, Language};
+///
+/// let mut container = InMemoryLanguageContainer::from_iter([
+///     Language::new("C").extensions(["h"]),
+///     Language::new("C++").aliases(["cpp"]).extensions(["h"]),
+/// ]);
+/// container.register_heuristic_rule(HeuristicRule::new(
+///     "cpp".to_string(),
+///     vec!["h".into()],
+///     vec![r"^\s*template\s*<".to_string()],
+/// ));
+///
+/// let lang = resolve_language_by_content("tests/fixtures/heuristics/vector.h", &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "C++");
+///
+/// // UTF-16 content with a byte order mark is decoded before the rules are applied
+/// let file = "tests/fixtures/encoding/vector.h";
+/// assert_eq!(resolve_language_by_content(file, &container).unwrap().unwrap().name, "C++");
+/// assert_eq!(resolve_language(file, &container).unwrap().unwrap().name, "C++");
+///
+/// // a later rule that requires more patterns is more specific than an earlier one
+/// let mut container = InMemoryLanguageContainer::from_iter([
+///     Language::new("C").extensions(["h"]),
+///     Language::new("Objective-C").extensions(["h"]),
+///     Language::new("Objective-C++").extensions(["h"]),
+/// ]);
+/// for (name, patterns) in [
+///     ("Objective-C", vec![r"^@interface"]),
+///     ("Objective-C++", vec![r"^@interface", r"^\s*template\s*<"]),
+///     ("C", vec![]),
+/// ] {
+///     let patterns = patterns.into_iter().map(String::from).collect();
+///     let rule = HeuristicRule::all_of(name.to_string(), vec!["h".into()], patterns);
+///     container.register_heuristic_rule(rule);
+/// }
+/// let resolve = |file| {
+///     let lang = resolve_language_by_content(file, &container).unwrap();
+///     lang.unwrap().name.as_str()
+/// };
+/// assert_eq!(resolve("tests/fixtures/heuristics/view.h"), "Objective-C");
+/// assert_eq!(resolve("tests/fixtures/heuristics/widget.h"), "Objective-C++");
+/// assert_eq!(resolve("tests/fixtures/ambiguous/header.h"), "C");
+/// ```
 #[cfg(feature = "matcher")]
 pub fn resolve_language_by_content(
     file: impl AsRef<Path>,
     container: &impl Container,
 ) -> Result<Option<&Language>, LinguistError> {
-    let content = match std::fs::read_to_string(file.as_ref()) {
-        Ok(content) => content,
+    resolve_language_by_content_with(file, container, &ResolveOptions::default())
+}
+
+/// Used to resolve all possible [`Language`]s by the file contents like
+/// [`resolve_language_by_content`], but only considers the first
+/// [`ResolveOptions::max_content_bytes`] of the file.
+#[cfg(feature = "matcher")]
+pub fn resolve_language_by_content_with<'a>(
+    file: impl AsRef<Path>,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Result<Option<&'a Language>, LinguistError> {
+    let max_bytes = options
+        .max_content_bytes
+        .unwrap_or(DEFAULT_MAX_CONTENT_BYTES);
+    let head = match read_head(file.as_ref(), max_bytes) {
+        Ok(head) => head,
         _ => return Err(LinguistError::FileNotFound),
     };
 
-    if let Some(rules) = container.get_heuristics_by_extension(file.as_ref()) {
-        for rule in rules {
-            let matcher = Regex::new(&rule.patterns.join("|"))?;
+    let candidates: Vec<&Language> = container
+        .languages_by_extension_iter(file.as_ref())
+        .collect();
+    match language_by_content(&head, Some(file.as_ref()), &candidates, container, options)? {
+        Some(lang) => Ok(Some(lang)),
+        None => Err(LinguistError::LanguageNotFound),
+    }
+}
+
+/// Used internally to resolve the [`Language`] by the given content. The configured
+/// [`ContentClassifier`] picks one of the given candidates; without one, the heuristic rules are
+/// applied if the name of the file is known.
+fn language_by_content<'a>(
+    raw: &[u8],
+    file: Option<&Path>,
+    candidates: &[&'a Language],
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Result<Option<&'a Language>, LinguistError> {
+    // the content may end within a multi-byte character
+    let content = String::from_utf8_lossy(raw);
+
+    if let Some(lang) = content
+        .lines()
+        .next()
+        .and_then(|line| container.get_language_by_first_line(line))
+    {
+        return Ok(Some(lang));
+    }
+
+    if let Some(classifier) = &options.content_classifier {
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+        return Ok(classifier.classify(raw, candidates));
+    }
+
+    if let Some(rules) = file.and_then(|file| container.get_heuristics_by_extension(file)) {
+        if let Some(rule) = most_specific_match(rules.iter(), &content)? {
+            return Ok(container.get_language_by_name(&rule.language));
+        }
+    }
 
-            if matcher.is_match(&content) {
-                return Ok(container.get_language_by_name(&rule.language));
-            }
+    Ok(None)
+}
+
+/// Used internally to pick the most specific of the given rules that match the content, i.e., the
+/// rule with the most components that have to match. Like in GitHub's Linguist, ties are decided by the order of the
+/// rules, so a rule without any patterns is only picked if no other rule matches.
+#[cfg(feature = "matcher")]
+pub(crate) fn most_specific_match<'r>(
+    rules: impl IntoIterator<Item = &'r HeuristicRule>,
+    content: &str,
+) -> Result<Option<&'r HeuristicRule>, LinguistError> {
+    let mut best: Option<&HeuristicRule> = None;
+    for rule in rules {
+        // a rule that is not more specific than the best one cannot win, so it is not evaluated
+        if best.is_some_and(|best| best.specificity() >= rule.specificity()) {
+            continue;
+        }
+        if rule.is_match(content)? {
+            best = Some(rule);
         }
     }
 
-    Err(LinguistError::LanguageNotFound)
+    Ok(best)
 }
 
-/// Used to resolve all possible [`Language`]s by the file contents.
+/// Used to resolve all possible [`Language`]s by the shebang of the file, see [`has_shebang`].
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_languages_by_shebang, Language};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Shell".to_string(),
+///     interpreters: vec!["sh".into()],
+///     ..Default::default()
+/// });
+///
+/// let langs = resolve_languages_by_shebang("tests/fixtures/templates/deploy.py.j2", &container);
+/// assert_eq!(langs.unwrap().unwrap()[0].name, "Shell");
+///
+/// // `#![no_std]` is an inner attribute, not a shebang
+/// let langs = resolve_languages_by_shebang("tests/fixtures/shebang/no_std.rs", &container);
+/// assert!(langs.unwrap().is_none());
+///
+/// // a UTF-8 byte order mark precedes the shebang
+/// container.register_language(Language::new("Python").interpreters(["python3"]));
+/// let langs = resolve_languages_by_shebang("tests/fixtures/encoding/run", &container);
+/// assert_eq!(langs.unwrap().unwrap()[0].name, "Python");
+///
+/// // a malformed shebang names no interpreter
+/// container.register_language(Language::new("Binary").interpreters(["bin"]));
+/// for file in ["empty", "root", "blank", "directory"] {
+///     let path = format!("tests/fixtures/shebang/{}", file);
+///     assert!(resolve_languages_by_shebang(path, &container).unwrap().is_none());
+/// }
+/// ```
 pub fn resolve_languages_by_shebang(
     file: impl AsRef<Path>,
     container: &impl Container,
 ) -> Result<Option<Vec<&Language>>, LinguistError> {
-    // load first line of file
-    let file = match std::fs::File::open(&file) {
-        Ok(file) => file,
-        Err(err) => return Err(LinguistError::IOError(err)),
+    let head = read_head(file.as_ref(), FIRST_FEW_BYTES)?;
+    Ok(languages_by_shebang(&head, container))
+}
+
+/// Used internally to resolve all possible [`Language`]s by the shebang at the beginning of the
+/// given content.
+fn languages_by_shebang<'a>(
+    head: &[u8],
+    container: &'a impl Container,
+) -> Option<Vec<&'a Language>> {
+    let shebang = parse_shebang(head)?;
+    container.get_languages_by_interpreter(&shebang.interpreter)
+}
+
+/// Resolve the [`Language`] of the given file by an Emacs or Vim modeline in its first or last few
+/// lines, see [`parse_modeline`]. The language is looked up by its name or alias. Only the first
+/// [`DEFAULT_MAX_CONTENT_BYTES`] of the file are scanned, so the last lines of a larger file are
+/// not considered.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language_by_modeline, Language};
+///
+/// let container = InMemoryLanguageContainer::from_iter([
+///     Language::new("C++").aliases(["cpp"]).extensions(["h"]),
+///     Language::new("Ruby").aliases(["rb"]).extensions(["rb"]),
+/// ]);
+///
+/// // `/* vim: set ft=cpp: */` is the last line
+/// let lang = resolve_language_by_modeline("tests/fixtures/modelines/string.h", &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "C++");
+///
+/// // `# -*- mode: ruby -*-` is the first line
+/// let lang = resolve_language_by_modeline("tests/fixtures/modelines/tasks.txt", &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "Ruby");
+///
+/// let lang = resolve_language_by_modeline("tests/fixtures/heuristics/vector.h", &container);
+/// assert!(lang.unwrap().is_none());
+/// ```
+pub fn resolve_language_by_modeline(
+    file: impl AsRef<Path>,
+    container: &impl Container,
+) -> Result<Option<&Language>, LinguistError> {
+    let head = read_head(file.as_ref(), DEFAULT_MAX_CONTENT_BYTES)?;
+    Ok(language_by_modeline(&head, container))
+}
+
+/// Used internally to resolve the [`Language`] by a modeline in the given content.
+fn language_by_modeline<'a>(head: &[u8], container: &'a impl Container) -> Option<&'a Language> {
+    // the content may end within a multi-byte character
+    let mode = parse_modeline(&String::from_utf8_lossy(head))?;
+    container.get_language_by_name(&mode)
+}
+
+/// Used internally to read at most `max_bytes` from the beginning of the given file. UTF-16 and
+/// UTF-32 text is decoded into UTF-8, see [`decode_head`].
+fn read_head(file: &Path, max_bytes: usize) -> Result<Vec<u8>, LinguistError> {
+    let mut head = Vec::new();
+    std::fs::File::open(file)?
+        .take(max_bytes as u64)
+        .read_to_end(&mut head)?;
+    Ok(decode_head(head))
+}
+
+/// Used internally to decode the given head of a file into UTF-8, so the signals can be matched
+/// regardless of the encoding, see [`decode_text`]. Valid UTF-8 without a byte order mark is
+/// returned as it is.
+fn decode_head(head: Vec<u8>) -> Vec<u8> {
+    match decode_text(&head) {
+        // a stripped byte order mark is borrowed as well
+        Cow::Borrowed(text) if text.len() == head.len() => head,
+        text => text.into_owned().into_bytes(),
+    }
+}
+
+/// An `AmbiguityPolicy` decides how a file is handled if several candidates share the highest
+/// score, e.g., a `.h` file without any matching content heuristic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AmbiguityPolicy {
+    /// Returns the first of the tied candidates in priority order, i.e., ordered by
+    /// [`ResolveOptions::prefer`] and [`ResolveOptions::tie_break`].
+    #[default]
+    FirstByPriority,
+    /// Returns no language at all, e.g., to flag the file for a manual review.
+    ReturnNone,
+    /// Does not pick one of the tied candidates; all candidates can be retrieved in priority order
+    /// with [`resolve_language_scored_with`] and the same options.
+    ReturnAll,
+}
+
+/// A `TieBreak` orders candidates that share the highest score and are not ranked by
+/// [`ResolveOptions::prefer`], so a file resolves to the same language on every run. Candidates
+/// that are still tied are ordered by name.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language_with, Language, ResolveOptions, TieBreak};
+///
+/// let container = InMemoryLanguageContainer::from_iter([
+///     Language::new("MATLAB").extensions(["m"]),
+///     Language::new("Objective-C").extensions(["m"]).popular(true),
+///     Language::new("Mathematica").extensions(["m"]),
+/// ]);
+///
+/// // there are no heuristics, so all candidates are tied
+/// let file = "tests/fixtures/ambiguous/Foundation.m";
+/// let resolve = |tie_break| {
+///     let options = ResolveOptions {
+///         tie_break,
+///         ..Default::default()
+///     };
+///     resolve_language_with(file, &container, &options)
+///         .unwrap()
+///         .map(|lang| lang.name.as_str())
+/// };
+///
+/// assert_eq!(resolve(TieBreak::Alphabetical), Some("Mathematica"));
+/// assert_eq!(resolve(TieBreak::PreferPopular), Some("Objective-C"));
+/// assert_eq!(resolve(TieBreak::FirstRegistered), Some("MATLAB"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Orders the candidates by name, ignoring ASCII case.
+    Alphabetical,
+    /// Orders the popular languages, see [`Language::popular`], before all other candidates.
+    #[default]
+    PreferPopular,
+    /// Orders the candidates by the position at which they were registered, see
+    /// [`Container::registration_index`].
+    FirstRegistered,
+}
+
+/// A `ShebangPrecedence` decides which signal wins if the shebang of a file contradicts its
+/// filename or extension.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language_with, Language, ResolveOptions, ShebangPrecedence};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Jinja".to_string(),
+///     extensions: vec!["j2".into(), "jinja".into()],
+///     ..Default::default()
+/// });
+/// container.register_language(Language {
+///     name: "Shell".to_string(),
+///     extensions: vec!["sh".into()],
+///     interpreters: vec!["sh".into()],
+///     ..Default::default()
+/// });
+///
+/// // the template starts with `#!/bin/sh`
+/// let file = "tests/fixtures/templates/deploy.py.j2";
+/// let resolve = |shebang_precedence| {
+///     let options = ResolveOptions {
+///         shebang_precedence,
+///         ..Default::default()
+///     };
+///     resolve_language_with(file, &container, &options)
+///         .unwrap()
+///         .map(|lang| lang.name.as_str())
+/// };
+///
+/// assert_eq!(resolve(ShebangPrecedence::PreferShebang), Some("Shell"));
+/// assert_eq!(resolve(ShebangPrecedence::PreferFilename), Some("Jinja"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShebangPrecedence {
+    /// The shebang outranks the filename and the extension, e.g., an extensionless script or a
+    /// `.txt` file with a `#!/usr/bin/env python` shebang resolves to Python.
+    #[default]
+    PreferShebang,
+    /// The filename and the extension outrank the shebang, e.g., for templates like
+    /// `deploy.py.j2` that carry the shebang of the rendered file.
+    PreferFilename,
+}
+
+/// The number of bytes at the beginning of a file that are scanned by the content heuristics,
+/// like GitHub's Linguist does.
+pub const DEFAULT_MAX_CONTENT_BYTES: usize = 50 * 1024;
+
+/// `ResolveOptions` are used to configure the behavior of [`resolve_language_with`].
+#[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    /// The policy that is applied if several candidates share the highest score.
+    pub on_ambiguous: AmbiguityPolicy,
+    /// Decides whether the shebang outranks the filename and extension or vice versa.
+    pub shebang_precedence: ShebangPrecedence,
+    /// Whether binary files are resolved by their magic number (see [`detect_magic`]) instead of
+    /// being skipped. The detected format is looked up as a language by its name.
+    pub detect_binary_formats: bool,
+    /// Extensions of files that are always treated as text, ignoring ASCII case. For these files
+    /// [`is_binary`] is not called, so files containing null bytes are resolved as well.
+    pub text_extensions: Vec<OsString>,
+    /// Extensions of files that are always treated as binary without reading them, ignoring ASCII
+    /// case. If an extension is also listed in `text_extensions`, the file is treated as text.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language_with, Language, ResolveOptions};
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([Language::new("Records").extensions(["dat"])]);
+    ///
+    /// // the file does not exist, so it cannot be checked for null bytes
+    /// let file = "tests/fixtures/missing/DUMP.DAT";
+    /// assert!(resolve_language_with(file, &container, &ResolveOptions::default()).is_err());
+    ///
+    /// let options = ResolveOptions {
+    ///     binary_extensions: vec!["dat".into()],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(resolve_language_with(file, &container, &options).unwrap(), None);
+    /// ```
+    pub binary_extensions: Vec<OsString>,
+    /// Whether a file whose name matches exactly one language, e.g., `Cargo.lock`, resolves to
+    /// that language right away. The file is not read at all, so neither its shebang nor its
+    /// content is considered.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language_with, Language, ResolveOptions, Scope};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// container.register_language(Language {
+    ///     name: "TOML".to_string(),
+    ///     scope: Scope::Data,
+    ///     extensions: vec!["toml".into()],
+    ///     filenames: vec!["Cargo.lock".into()],
+    ///     ..Default::default()
+    /// });
+    ///
+    /// // the file does not exist, so the full vote fails to read it
+    /// let file = "tests/fixtures/missing/Cargo.lock";
+    /// assert!(resolve_language_with(file, &container, &ResolveOptions::default()).is_err());
+    ///
+    /// let options = ResolveOptions {
+    ///     short_circuit_on_filename: true,
+    ///     ..Default::default()
+    /// };
+    /// let lang = resolve_language_with(file, &container, &options).unwrap();
+    /// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("TOML"));
+    /// ```
+    pub short_circuit_on_filename: bool,
+    /// Whether a file whose extension matches exactly one language resolves to that language right
+    /// away, like [`ResolveOptions::short_circuit_on_filename`]. Exact filename matches are still
+    /// counted first when both options are enabled.
+    pub short_circuit_on_extension: bool,
+    /// The scopes of languages that are counted by
+    /// [`classify_file`](crate::classifier::classify_file) and
+    /// [`classify_directory_with`](crate::classifier::classify_directory_with). `None` counts
+    /// languages of all scopes.
+    pub scopes: Option<HashSet<Scope>>,
+    /// Whether [`classify_directory_with`](crate::classifier::classify_directory_with) records the
+    /// bytes of filtered files by their [`FilterReason`](crate::classifier::FilterReason), see
+    /// [`LanguageBreakdown::add_filtered`](crate::stats::LanguageBreakdown::add_filtered).
+    ///
+    /// # Example
+    /// ```
+    /// use regex::RegexSet;
+    /// use linguist::classifier::{classify_directory_with, FilterReason, Filters};
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{Language, ResolveOptions};
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Rust").extensions(["rs"]),
+    ///     Language::new("JavaScript").extensions(["js"]),
+    /// ]);
+    /// let root = "tests/fixtures/vendored-js";
+    /// let filters = Filters::new().vendors(RegexSet::new([r"(^|/)vendor/"]).unwrap());
+    ///
+    /// let breakdown = classify_directory_with(root, &container, &filters, &ResolveOptions::default()).unwrap();
+    /// assert_eq!(breakdown.total_filtered_size(), 0);
+    ///
+    /// let options = ResolveOptions {
+    ///     track_filtered: true,
+    ///     ..Default::default()
+    /// };
+    /// let breakdown = classify_directory_with(root, &container, &filters, &options).unwrap();
+    /// let vendored = std::fs::metadata("tests/fixtures/vendored-js/vendor/tiny-dom.js").unwrap();
+    /// assert_eq!(breakdown.filtered_size(FilterReason::Vendored), Some(vendored.len()));
+    /// assert_eq!(breakdown.usage("JavaScript"), None);
+    /// assert!(breakdown.usage("Rust").is_some());
+    /// ```
+    pub track_filtered: bool,
+    /// The classifier that resolves the language by the content of a file. `None` applies the
+    /// heuristic rules of the container, like [`HeuristicClassifier`] does.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language_with, ContentClassifier, Language, ResolveOptions};
+    ///
+    /// /// Picks C++ for any content that mentions templates.
+    /// #[derive(Debug)]
+    /// struct TemplateClassifier;
+    ///
+    /// impl ContentClassifier for TemplateClassifier {
+    ///     fn classify<'a>(&self, content: &[u8], candidates: &[&'a Language]) -> Option<&'a Language> {
+    ///         if !content.windows(8).any(|word| word == b"template") {
+    ///             return None;
+    ///         }
+    ///         candidates.iter().copied().find(|lang| lang.name == "C++")
+    ///     }
+    /// }
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for name in ["C", "C++"] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         extensions: vec!["h".into()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// let file = "tests/fixtures/heuristics/vector.h";
+    /// let lang = resolve_language_with(file, &container, &ResolveOptions::default()).unwrap();
+    /// assert_eq!(lang.unwrap().name, "C");
+    ///
+    /// let options = ResolveOptions {
+    ///     content_classifier: Some(Arc::new(TemplateClassifier)),
+    ///     ..Default::default()
+    /// };
+    /// let lang = resolve_language_with(file, &container, &options).unwrap();
+    /// assert_eq!(lang.unwrap().name, "C++");
+    /// ```
+    pub content_classifier: Option<Arc<dyn ContentClassifier>>,
+    /// The number of bytes at the beginning of a file that are scanned by the content heuristics,
+    /// which bounds the cost of matching large files. `None` scans the first
+    /// [`DEFAULT_MAX_CONTENT_BYTES`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language_with, HeuristicRule, Language, ResolveOptions};
+    ///
+    /// let mut container = InMemoryLanguageContainer::default();
+    /// for name in ["C", "C++"] {
+    ///     container.register_language(Language {
+    ///         name: name.to_string(),
+    ///         extensions: vec!["h".into()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "C++".to_string(),
+    ///     vec!["h".into()],
+    ///     vec![r"^\s*template\s*<".to_string()],
+    /// ));
+    ///
+    /// let file = "tests/fixtures/window/late.h";
+    /// let lang = resolve_language_with(file, &container, &ResolveOptions::default());
+    /// assert_eq!(lang.unwrap().unwrap().name, "C++");
+    ///
+    /// // the template is beyond the first 32 bytes, so the candidates are tied
+    /// let options = ResolveOptions {
+    ///     max_content_bytes: Some(32),
+    ///     ..Default::default()
+    /// };
+    /// let lang = resolve_language_with(file, &container, &options);
+    /// assert_eq!(lang.unwrap().unwrap().name, "C");
+    /// ```
+    pub max_content_bytes: Option<usize>,
+    /// Names or aliases of languages that win a tie, in order of preference, e.g., to lean toward
+    /// the primary stack of a project. A preferred language only wins among candidates with the
+    /// same score, so it never overrides a stronger signal; a tie that is broken by a preference
+    /// is not ambiguous, see [`ResolveOptions::on_ambiguous`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{resolve_language_with, AmbiguityPolicy, Language, ResolveOptions};
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("C").extensions(["c", "h"]),
+    ///     Language::new("C++").aliases(["cpp"]).extensions(["cpp", "h"]),
+    /// ]);
+    /// let file = "tests/fixtures/heuristics/vector.h";
+    ///
+    /// let options = ResolveOptions {
+    ///     on_ambiguous: AmbiguityPolicy::ReturnNone,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(resolve_language_with(file, &container, &options).unwrap(), None);
+    ///
+    /// let options = ResolveOptions {
+    ///     prefer: vec!["cpp".to_string()],
+    ///     ..options
+    /// };
+    /// let lang = resolve_language_with(file, &container, &options).unwrap();
+    /// assert_eq!(lang.unwrap().name, "C++");
+    /// ```
+    pub prefer: Vec<String>,
+    /// The order of candidates that share the highest score and are not ranked by
+    /// [`ResolveOptions::prefer`]. [`resolve_language`] orders the
+    /// [popular](TieBreak::PreferPopular) languages first, and the others by name. The order
+    /// decides which candidate is returned with [`AmbiguityPolicy::FirstByPriority`], but the tie
+    /// is still ambiguous.
+    pub tie_break: TieBreak,
+    /// The attributes of a `.gitattributes` file whose `linguist-language` overrides the resolved
+    /// language, see [`GitAttributes::language`]. The forced language is looked up by its name or
+    /// alias; an unknown language is ignored. The path of the file is matched as given to
+    /// [`resolve_language_with`], so it is expected to be relative to the directory of the
+    /// `.gitattributes` file, while [`classify_file`](crate::classifier::classify_file) matches the
+    /// path relative to its root.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs::File;
+    ///
+    /// use linguist::classifier::{classify_directory_with, Filters};
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::gitattributes::parse_gitattributes;
+    /// use linguist::resolver::{Language, ResolveOptions};
+    ///
+    /// let container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Rust").extensions(["rs"]),
+    ///     Language::new("PHP").extensions(["php"]),
+    ///     Language::new("Markdown").extensions(["md"]),
+    /// ]);
+    ///
+    /// let file = File::open("tests/fixtures/overrides/.gitattributes").unwrap();
+    /// let attributes = parse_gitattributes(file).unwrap();
+    ///
+    /// // `*.inc` is forced to PHP, `docs/` is documentation and `src/generated/` is generated
+    /// let root = "tests/fixtures/overrides";
+    /// let filters = Filters::new().attributes(attributes.clone());
+    /// let options = ResolveOptions {
+    ///     attributes: Some(attributes),
+    ///     ..Default::default()
+    /// };
+    /// let breakdown = classify_directory_with(root, &container, &filters, &options).unwrap();
+    ///
+    /// let helpers = std::fs::metadata("tests/fixtures/overrides/lib/helpers.inc").unwrap();
+    /// let main = std::fs::metadata("tests/fixtures/overrides/src/main.rs").unwrap();
+    /// assert_eq!(breakdown.usage("PHP"), Some(helpers.len()));
+    /// assert_eq!(breakdown.usage("Rust"), Some(main.len()));
+    /// assert_eq!(breakdown.usage("Markdown"), None);
+    /// ```
+    pub attributes: Option<GitAttributes>,
+    /// Whether the content of a file is ignored, i.e., its modeline, first-line marker, and the
+    /// heuristic rules, e.g., for a fast first pass over a large tree that only resolves files by
+    /// their filename, extension, and shebang.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::container::InMemoryLanguageContainer;
+    /// use linguist::resolver::{
+    ///     resolve_language_with, AmbiguityPolicy, HeuristicRule, Language, ResolveOptions,
+    /// };
+    ///
+    /// let mut container = InMemoryLanguageContainer::from_iter([
+    ///     Language::new("Perl").extensions(["t"]),
+    ///     Language::new("Raku").extensions(["t"]),
+    ///     Language::new("Rust").extensions(["rs"]),
+    /// ]);
+    /// container.register_heuristic_rule(HeuristicRule::new(
+    ///     "Raku".to_string(),
+    ///     vec!["t".into()],
+    ///     vec![r"^\s*use\s+v6\b".to_string()],
+    /// ));
+    ///
+    /// // the fast pass does not read the files at all, so a missing file resolves as well
+    /// let fast = ResolveOptions {
+    ///     on_ambiguous: AmbiguityPolicy::ReturnNone,
+    ///     skip_content: true,
+    ///     skip_shebang: true,
+    ///     skip_binary_check: true,
+    ///     ..Default::default()
+    /// };
+    /// let lang = resolve_language_with("tests/fixtures/missing/lib.rs", &container, &fast);
+    /// assert_eq!(lang.unwrap().unwrap().name, "Rust");
+    ///
+    /// // ambiguous files are left to a second pass that considers the content
+    /// let file = "tests/fixtures/ambiguous/plan.t";
+    /// assert_eq!(resolve_language_with(file, &container, &fast).unwrap(), None);
+    /// let lang = resolve_language_with(file, &container, &ResolveOptions::default());
+    /// assert_eq!(lang.unwrap().unwrap().name, "Raku");
+    /// ```
+    pub skip_content: bool,
+    /// Whether the shebang of a file is ignored. If the content is ignored as well, the file is
+    /// not read to resolve its language.
+    pub skip_shebang: bool,
+    /// Whether files are treated as text without checking their content for binary data. Files
+    /// with one of the [`ResolveOptions::binary_extensions`] are still treated as binary.
+    pub skip_binary_check: bool,
+}
+
+/// Used internally to check whether the given file is binary. The extension lists of the given
+/// options take precedence over [`is_binary`].
+pub(crate) fn check_binary(
+    file: impl AsRef<Path>,
+    options: &ResolveOptions,
+) -> Result<bool, LinguistError> {
+    if let Some(ext) = file.as_ref().extension() {
+        if options
+            .text_extensions
+            .iter()
+            .any(|text| text.eq_ignore_ascii_case(ext))
+        {
+            return Ok(false);
+        }
+        if options
+            .binary_extensions
+            .iter()
+            .any(|binary| binary.eq_ignore_ascii_case(ext))
+        {
+            return Ok(true);
+        }
+    }
+
+    if options.skip_binary_check {
+        return Ok(false);
+    }
+
+    is_binary(file)
+}
+
+/// Used internally to return the only language of the given candidates, if there is exactly one.
+fn single_language<'a>(mut candidates: impl Iterator<Item = &'a Language>) -> Option<&'a Language> {
+    let first = candidates.next()?;
+    match candidates.next() {
+        Some(_) => None,
+        _ => Some(first),
+    }
+}
+
+/// Used internally to resolve the [`Language`] of a binary file by its magic number.
+pub(crate) fn resolve_language_by_magic(
+    file: impl AsRef<Path>,
+    container: &impl Container,
+) -> Result<Option<&Language>, LinguistError> {
+    let mut buf = [0; 16];
+    let n = std::fs::File::open(file.as_ref())?.read(&mut buf)?;
+
+    Ok(detect_magic(&buf[..n]).and_then(|name| container.get_language_by_name(name)))
+}
+
+/// Used internally to weight the signals of all candidates for the given file. The file is read
+/// from `file`, while filename and extension rules are matched against `relative`, i.e., the path
+/// relative to the project root. The candidates are ordered by their weight and, in case of a tie,
+/// by the given options, see [`ResolveOptions::tie_break`].
+fn count_signals<'a>(
+    file: impl AsRef<Path>,
+    relative: &Path,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Vec<(&'a Language, usize)> {
+    let head = read_signal_head(file.as_ref(), options);
+    weigh_signals(Some(relative), &head, container, options)
+}
+
+/// Used internally to read the beginning of the given file that is needed by the shebang and
+/// content signals. An unreadable file only has filename and extension signals.
+fn read_signal_head(file: &Path, options: &ResolveOptions) -> Vec<u8> {
+    if options.skip_content && options.skip_shebang {
+        return Vec::new();
+    }
+
+    let max_bytes = options
+        .max_content_bytes
+        .unwrap_or(DEFAULT_MAX_CONTENT_BYTES);
+    read_head(file, max_bytes.max(FIRST_FEW_BYTES)).unwrap_or_default()
+}
+
+/// Used internally to weight the signals of all candidates for a file with the given name, if
+/// known, and the given content. See [`count_signals`].
+fn weigh_signals<'a>(
+    name: Option<&Path>,
+    head: &[u8],
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Vec<(&'a Language, usize)> {
+    let signals = collect_signals(name, head, container, options);
+    order_candidates(&sum_signals(&signals), container, options)
+}
+
+/// Used internally to collect the candidates of every signal that fires for a file with the given
+/// name, if known, and the given content, together with the weight of the signal. The signals are
+/// evaluated in order; the content signal picks one of the candidates of the previous ones.
+fn collect_signals<'a>(
+    name: Option<&Path>,
+    head: &[u8],
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Vec<(Signal, usize, Vec<&'a Language>)> {
+    // an exact filename outweighs an extension even if the content agrees with the extension,
+    // e.g., `CMakeLists.txt` is CMake and not Text
+    let (filename_weight, extension_weight, shebang_weight) = match options.shebang_precedence {
+        ShebangPrecedence::PreferShebang => (3, 1, 4),
+        ShebangPrecedence::PreferFilename => (4, 2, 1),
     };
-    let mut buf = BufReader::new(file);
-    let mut line = String::new();
-    let _ = buf.read_line(&mut line);
 
-    // check whether the first line of the file is a shebang
-    if !has_shebang(line.as_bytes()) {
-        return Ok(None);
+    let mut signals: Vec<(Signal, usize, Vec<&Language>)> = Vec::new();
+
+    // like GitHub's Linguist, a modeline overrides all other signals, so it outweighs them combined
+    let modeline = match options.skip_content {
+        false => language_by_modeline(head, container),
+        true => None,
+    };
+    if let Some(lang) = modeline {
+        signals.push((Signal::Modeline, 10, vec![lang]));
     }
 
-    let line = line[2..].trim();
-    let mut fields = line.split_whitespace().collect::<Vec<&str>>();
-    if fields.is_empty() {
-        return Ok(None);
+    if let Some(name) = name {
+        let filename = name.file_name().map(Path::new).unwrap_or(name);
+        let candidates = container.languages_by_filename_iter(filename).collect();
+        signals.push((Signal::Filename, filename_weight, candidates));
     }
 
-    let mut interpreter = Path::new(fields[0])
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_owned();
+    let shebang = match options.skip_shebang {
+        false => languages_by_shebang(head, container),
+        true => None,
+    };
+    if let Some(candidates) = shebang {
+        signals.push((Signal::Shebang, shebang_weight, candidates));
+    }
 
-    if interpreter == "env" {
-        if fields.len() == 1 {
-            return Ok(None);
-        }
+    if let Some(name) = name {
+        let candidates = container.languages_by_extension_iter(name).collect();
+        signals.push((Signal::Extension, extension_weight, candidates));
+    }
+
+    if options.skip_content {
+        signals.retain(|(_, _, candidates)| !candidates.is_empty());
+        return signals;
+    }
 
-        let env_opt_args = Regex::new(r"^-[a-zA-Z]+$").unwrap();
-        let env_var_args = Regex::new(r"^\$[a-zA-Z_]+$").unwrap();
+    let max_bytes = options
+        .max_content_bytes
+        .unwrap_or(DEFAULT_MAX_CONTENT_BYTES);
+    let content = &head[..head.len().min(max_bytes)];
+    let candidates: Vec<&Language> = order_candidates(&sum_signals(&signals), container, options)
+        .into_iter()
+        .map(|(lang, _)| lang)
+        .collect();
+    if let Ok(Some(candidate)) = language_by_content(content, name, &candidates, container, options)
+    {
+        signals.push((Signal::Content, 1, vec![candidate]));
+    }
+
+    signals.retain(|(_, _, candidates)| !candidates.is_empty());
+    signals
+}
 
-        let _i = 1;
-        while fields.len() > 2 {
-            if env_opt_args.is_match(fields[1]) || env_var_args.is_match(fields[1]) {
-                fields.remove(1);
-                continue;
-            }
-            break;
+/// Used internally to sum up the weights of the given signals per candidate.
+fn sum_signals(signals: &[(Signal, usize, Vec<&Language>)]) -> HashMap<String, usize> {
+    let mut probabilities: HashMap<String, usize> = HashMap::new();
+    for (_, weight, candidates) in signals {
+        for candidate in candidates {
+            *probabilities
+                .entry(candidate.name.to_ascii_lowercase())
+                .or_insert(0) += weight;
         }
-        interpreter = Path::new(fields[1])
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_owned();
     }
 
-    let mut interpreter = interpreter;
-    if interpreter == "sh" {
-        interpreter = determine_multiline_exec(buf.buffer()).unwrap();
+    probabilities
+}
+
+/// Used internally to order the weighted candidates by their weight and, in case of a tie, by
+/// preference (see [`ResolveOptions::prefer`]) and [`ResolveOptions::tie_break`].
+fn order_candidates<'a>(
+    probabilities: &HashMap<String, usize>,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Vec<(&'a Language, usize)> {
+    // the registration order is looked up once per candidate instead of in every comparison
+    let registered = |lang| match options.tie_break {
+        TieBreak::FirstRegistered => container.registration_index(lang).unwrap_or(usize::MAX),
+        _ => 0,
+    };
+    let mut ordered: Vec<(&Language, usize, usize)> = probabilities
+        .iter()
+        .filter_map(|(name, &count)| {
+            container
+                .get_language_by_name(name)
+                .map(|lang| (lang, count, registered(lang)))
+        })
+        .collect();
+    ordered.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then_with(|| preference(a.0, options).cmp(&preference(b.0, options)))
+            .then_with(|| break_tie((a.0, a.2), (b.0, b.2), options.tie_break))
+    });
+    ordered
+        .into_iter()
+        .map(|(lang, count, _)| (lang, count))
+        .collect()
+}
+
+/// Used internally to order two tied candidates, each with its
+/// [`Container::registration_index`], by the given [`TieBreak`] and, if they are still tied, by
+/// name.
+fn break_tie(
+    (a, a_index): (&Language, usize),
+    (b, b_index): (&Language, usize),
+    tie_break: TieBreak,
+) -> Ordering {
+    let ordering = match tie_break {
+        TieBreak::Alphabetical => Ordering::Equal,
+        TieBreak::PreferPopular => b.popular.cmp(&a.popular),
+        TieBreak::FirstRegistered => a_index.cmp(&b_index),
+    };
+
+    ordering.then_with(|| {
+        a.name
+            .to_ascii_lowercase()
+            .cmp(&b.name.to_ascii_lowercase())
+    })
+}
+
+/// Used internally to rank the given language by its position in [`ResolveOptions::prefer`]. A
+/// language that is not listed ranks last.
+fn preference(lang: &Language, options: &ResolveOptions) -> usize {
+    options
+        .prefer
+        .iter()
+        .position(|name| {
+            lang.name.eq_ignore_ascii_case(name)
+                || lang
+                    .aliases
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(name))
+        })
+        .unwrap_or(usize::MAX)
+}
+
+/// A `Signal` is a piece of evidence the resolver weighs, see [`resolve_traced`] and
+/// [`resolve_language_explained`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Signal {
+    /// An Emacs or Vim modeline, see [`resolve_language_by_modeline`].
+    Modeline,
+    /// The exact name of the file, e.g., `Makefile`.
+    Filename,
+    /// The interpreter of the shebang, see [`resolve_languages_by_shebang`].
+    Shebang,
+    /// The extension of the file.
+    Extension,
+    /// A first-line marker or the content classification, see [`resolve_language_by_content`].
+    Content,
+}
+
+/// A `SignalTrace` records the candidates of a [`Signal`] that fired and the weight it added to
+/// each of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SignalTrace {
+    pub signal: Signal,
+    pub weight: usize,
+    /// The names of the candidates in the order of the container.
+    pub candidates: Vec<String>,
+}
+
+/// A `ScoreTrace` records the final weight of a candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ScoreTrace {
+    pub language: String,
+    pub weight: usize,
+}
+
+/// A `ResolutionTrace` records the evidence that led to the resolved language of a file, e.g., to
+/// snapshot the behavior of the resolver in a test or to attach to a bug report. See
+/// [`resolve_traced`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResolutionTrace {
+    /// Whether the file is binary. Binary files are not resolved, so they have no signals.
+    pub binary: bool,
+    /// The signals that fired, in the order they are evaluated.
+    pub signals: Vec<SignalTrace>,
+    /// The final weight of every candidate, ordered by weight and, in case of a tie, by name.
+    pub scores: Vec<ScoreTrace>,
+    /// The name of the resolved language, like [`resolve_language`] returns it.
+    pub language: Option<String>,
+}
+
+/// Resolves the [`Language`] of the given file like [`resolve_language`], but returns the full
+/// [`ResolutionTrace`] instead of only the winner.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_traced, Language, Signal};
+///
+/// let container = InMemoryLanguageContainer::from_iter([
+///     Language::new("Jinja").extensions(["j2", "jinja"]),
+///     Language::new("Shell").extensions(["sh"]).interpreters(["sh"]),
+/// ]);
+///
+/// let trace = resolve_traced("tests/fixtures/templates/deploy.py.j2", &container).unwrap();
+/// assert_eq!(trace.signals[0].signal, Signal::Shebang);
+/// assert_eq!(trace.language.as_deref(), Some("Shell"));
+///
+/// let snapshot = serde_yaml::to_string(&trace).unwrap();
+/// assert_eq!(
+///     snapshot,
+///     "\
+/// binary: false
+/// signals:
+/// - signal: Shebang
+///   weight: 4
+///   candidates:
+///   - Shell
+/// - signal: Extension
+///   weight: 1
+///   candidates:
+///   - Jinja
+/// scores:
+/// - language: Shell
+///   weight: 4
+/// - language: Jinja
+///   weight: 1
+/// language: Shell
+/// "
+/// );
+/// ```
+pub fn resolve_traced(
+    file: impl AsRef<Path>,
+    container: &impl Container,
+) -> Result<ResolutionTrace, LinguistError> {
+    let options = ResolveOptions::default();
+    if check_binary(&file, &options)? {
+        return Ok(ResolutionTrace {
+            binary: true,
+            ..Default::default()
+        });
     }
 
-    let python_version = Regex::new(r"^python[0-9]*\.[0-9]*").unwrap();
-    if python_version.is_match(&interpreter) {
-        interpreter = interpreter.split('.').next().unwrap().to_owned();
+    let head = read_signal_head(file.as_ref(), &options);
+    let signals = collect_signals(Some(file.as_ref()), &head, container, &options);
+    let scores = order_candidates(&sum_signals(&signals), container, &options);
+    let language = pick_candidate(&scores, &options).ok().flatten();
+
+    Ok(ResolutionTrace {
+        binary: false,
+        signals: signals
+            .into_iter()
+            .map(|(signal, weight, candidates)| SignalTrace {
+                signal,
+                weight,
+                candidates: candidates.iter().map(|lang| lang.name.clone()).collect(),
+            })
+            .collect(),
+        scores: scores
+            .iter()
+            .map(|(lang, weight)| ScoreTrace {
+                language: lang.name.clone(),
+                weight: *weight,
+            })
+            .collect(),
+        language: language.map(|lang| lang.name.clone()),
+    })
+}
+
+/// A `Resolution` is the resolved [`Language`] of a file together with the [`Signal`] that decided
+/// it, see [`resolve_language_explained`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution<'a> {
+    pub language: &'a Language,
+    /// The heaviest signal that names the language. If several signals weigh the same, the one
+    /// with the fewest candidates decided, e.g., the content over an ambiguous extension.
+    pub via: Signal,
+}
+
+/// Resolves the [`Language`] of the given file like [`resolve_language`], but also returns the
+/// [`Signal`] that decided it, e.g., to debug a misclassified file. See [`resolve_traced`] for all
+/// signals and scores.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language_explained, HeuristicRule, Language, Signal};
+///
+/// let mut container = InMemoryLanguageContainer::from_iter([
+///     Language::new("C").extensions(["h"]),
+///     Language::new("MATLAB").extensions(["m"]),
+///     Language::new("Objective-C").extensions(["m"]),
+///     Language::new("CMake").filenames(["CMakeLists.txt"]),
+///     Language::new("Jinja").extensions(["j2"]),
+///     Language::new("Shell").interpreters(["sh"]),
+///     Language::new("Ruby"),
+///     Language::new("Text").extensions(["txt"]),
+/// ]);
+/// container.register_heuristic_rule(HeuristicRule::new(
+///     "Objective-C".to_string(),
+///     vec!["m".into()],
+///     vec![r"^\s*@interface\b".to_string()],
+/// ));
+///
+/// let via = |file| {
+///     let resolution = resolve_language_explained(file, &container).unwrap().unwrap();
+///     (resolution.language.name.as_str(), resolution.via)
+/// };
+/// assert_eq!(via("tests/fixtures/ambiguous/Foundation.m"), ("Objective-C", Signal::Content));
+/// assert_eq!(via("tests/fixtures/ambiguous/header.h"), ("C", Signal::Extension));
+/// assert_eq!(via("tests/fixtures/filenames/CMakeLists.txt"), ("CMake", Signal::Filename));
+/// assert_eq!(via("tests/fixtures/templates/deploy.py.j2"), ("Shell", Signal::Shebang));
+/// assert_eq!(via("tests/fixtures/modelines/tasks.txt"), ("Ruby", Signal::Modeline));
+///
+/// let binary = resolve_language_explained("tests/fixtures/binary/records.dat", &container);
+/// assert_eq!(binary.unwrap(), None);
+/// ```
+pub fn resolve_language_explained(
+    file: impl AsRef<Path>,
+    container: &impl Container,
+) -> Result<Option<Resolution<'_>>, LinguistError> {
+    let options = ResolveOptions::default();
+    if check_binary(&file, &options)? {
+        return Ok(None);
     }
-    // If osascript is called with argument -l it could be different language so do not rely on it
-    // To match linguist behavior, see ref https://github.com/github/linguist/blob/d95bae794576ab0ef2fcb41a39eb61ea5302c5b5/lib/linguist/shebang.rb#L63
-    if interpreter == "osascript" && line.contains("-l") {
-        interpreter = "".to_string();
+
+    let head = read_signal_head(file.as_ref(), &options);
+    let signals = collect_signals(Some(file.as_ref()), &head, container, &options);
+    let scores = order_candidates(&sum_signals(&signals), container, &options);
+    let language = match pick_candidate(&scores, &options)? {
+        Some(language) => language,
+        _ => return Ok(None),
+    };
+
+    Ok(signals
+        .iter()
+        .filter(|(_, _, candidates)| {
+            candidates
+                .iter()
+                .any(|candidate| candidate.name.eq_ignore_ascii_case(&language.name))
+        })
+        .min_by_key(|(_, weight, candidates)| (Reverse(*weight), candidates.len()))
+        .map(|&(signal, _, _)| Resolution {
+            language,
+            via: signal,
+        }))
+}
+
+/// Resolves all candidate [`Language`]s of the given file together with a score in `[0.0, 1.0]`.
+/// The candidates are ordered by their score and, in case of a tie, by popularity and name, see
+/// [`TieBreak::PreferPopular`]. A binary file has no candidates.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language_scored, HeuristicRule, Language};
+///
+/// let mut container = InMemoryLanguageContainer::from_iter([
+///     Language::new("Coq").extensions(["v"]),
+///     Language::new("Verilog").extensions(["v"]),
+///     Language::new("Records").extensions(["dat"]),
+/// ]);
+/// container.register_heuristic_rule(HeuristicRule::new(
+///     "Verilog".to_string(),
+///     vec!["v".into()],
+///     vec![r"^\s*module\b".to_string()],
+/// ));
+///
+/// // both languages share the extension, but only Verilog matches the content
+/// let candidates = resolve_language_scored("tests/fixtures/ambiguous/counter.v", &container).unwrap();
+/// let names: Vec<&str> = candidates.iter().map(|(lang, _)| lang.name.as_str()).collect();
+/// assert_eq!(names, ["Verilog", "Coq"]);
+/// assert!(candidates[0].1 > candidates[1].1);
+/// assert_eq!(candidates.iter().map(|(_, score)| score).sum::<f64>(), 1.0);
+///
+/// let candidates = resolve_language_scored("tests/fixtures/binary/records.dat", &container).unwrap();
+/// assert!(candidates.is_empty());
+/// ```
+pub fn resolve_language_scored(
+    file: impl AsRef<Path>,
+    container: &impl Container,
+) -> Result<Vec<(&Language, f64)>, LinguistError> {
+    resolve_language_scored_with(file, container, &ResolveOptions::default())
+}
+
+/// Resolves all candidate [`Language`]s of the given file together with a score in `[0.0, 1.0]`,
+/// like [`resolve_language_scored`], but with the given options. Tied candidates are ordered by
+/// [`ResolveOptions::prefer`] and [`ResolveOptions::tie_break`], i.e., the first candidate is the
+/// one [`resolve_language_with`] picks with [`AmbiguityPolicy::FirstByPriority`]. A short
+/// circuited file, see [`ResolveOptions::short_circuit_on_filename`], has a single candidate.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{
+///     resolve_language_scored_with, resolve_language_with, AmbiguityPolicy, Language,
+///     ResolveOptions, TieBreak,
+/// };
+///
+/// let container = InMemoryLanguageContainer::from_iter([
+///     Language::new("C").extensions(["h"]),
+///     Language::new("C++").extensions(["h"]).popular(true),
+/// ]);
+///
+/// let file = "tests/fixtures/ambiguous/header.h";
+/// let options = ResolveOptions {
+///     on_ambiguous: AmbiguityPolicy::ReturnAll,
+///     tie_break: TieBreak::PreferPopular,
+///     ..Default::default()
+/// };
+/// assert_eq!(resolve_language_with(file, &container, &options).unwrap(), None);
+///
+/// let candidates = resolve_language_scored_with(file, &container, &options).unwrap();
+/// let names: Vec<&str> = candidates.iter().map(|(lang, _)| lang.name.as_str()).collect();
+/// assert_eq!(names, ["C++", "C"]);
+/// assert_eq!(candidates[0].1, candidates[1].1);
+/// ```
+pub fn resolve_language_scored_with<'a>(
+    file: impl AsRef<Path>,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Result<Vec<(&'a Language, f64)>, LinguistError> {
+    let relative = file.as_ref();
+    if let Some(lang) = short_circuit_language(relative, container, options) {
+        return Ok(vec![(lang, 1.0)]);
     }
 
-    let results = container.get_languages_by_interpreter(&interpreter);
-    if results.is_some() {
-        Ok(results)
-    } else {
-        Ok(None)
+    if check_binary(&file, options)? {
+        if options.detect_binary_formats {
+            let lang = resolve_language_by_magic(&file, container)?;
+            return Ok(lang.into_iter().map(|lang| (lang, 1.0)).collect());
+        }
+        return Ok(vec![]);
     }
+
+    let candidates = count_signals(&file, relative, container, options);
+    let total: usize = candidates.iter().map(|&(_, count)| count).sum();
+
+    Ok(candidates
+        .into_iter()
+        .map(|(lang, count)| (lang, count as f64 / total as f64))
+        .collect())
 }
 
-/// Resolve the [`Language`] of the given file. It will try to resolve the language by the filename,
-/// extension, shebang and content. The most likely language will be returned.
-pub fn resolve_language(
+/// Resolve the [`Language`] of the given file. It will try to resolve the language by the modeline,
+/// filename, extension, shebang and content. The most likely language will be returned. If several
+/// candidates are equally likely, the configured [`AmbiguityPolicy`] is applied. Binary files
+/// resolve to `None`, unless their extension is listed in [`ResolveOptions::text_extensions`].
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{
+///     resolve_language_scored_with, resolve_language_with, AmbiguityPolicy, Language,
+///     ResolveOptions, Scope,
+/// };
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// for name in ["C", "C++"] {
+///     container.register_language(Language {
+///         name: name.to_string(),
+///         scope: Scope::Programming,
+///         extensions: vec!["h".into()],
+///         ..Default::default()
+///     });
+/// }
+///
+/// let file = "tests/fixtures/ambiguous/header.h";
+/// let resolve = |on_ambiguous| {
+///     let options = ResolveOptions {
+///         on_ambiguous,
+///         ..Default::default()
+///     };
+///     resolve_language_with(file, &container, &options)
+///         .unwrap()
+///         .map(|lang| lang.name.as_str())
+/// };
+///
+/// assert_eq!(resolve(AmbiguityPolicy::FirstByPriority), Some("C"));
+/// assert_eq!(resolve(AmbiguityPolicy::ReturnNone), None);
+/// assert_eq!(resolve(AmbiguityPolicy::ReturnAll), None);
+///
+/// let options = ResolveOptions {
+///     on_ambiguous: AmbiguityPolicy::ReturnAll,
+///     ..Default::default()
+/// };
+/// let candidates = resolve_language_scored_with(file, &container, &options).unwrap();
+/// assert_eq!(candidates.len(), 2);
+/// assert_eq!(candidates[0].1, candidates[1].1);
+///
+/// // `records.dat` contains a null byte and would be skipped as binary
+/// container.register_language(Language {
+///     name: "Records".to_string(),
+///     scope: Scope::Data,
+///     extensions: vec!["dat".into()],
+///     ..Default::default()
+/// });
+///
+/// let file = "tests/fixtures/binary/records.dat";
+/// let options = ResolveOptions::default();
+/// assert_eq!(resolve_language_with(file, &container, &options).unwrap(), None);
+///
+/// let options = ResolveOptions {
+///     text_extensions: vec!["dat".into()],
+///     ..Default::default()
+/// };
+/// let lang = resolve_language_with(file, &container, &options).unwrap();
+/// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("Records"));
+/// ```
+pub fn resolve_language_with<'a>(
+    file: impl AsRef<Path>,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Result<Option<&'a Language>, LinguistError> {
+    resolve_language_anchored(&file, file.as_ref(), container, options)
+}
+
+/// Resolve the [`Language`] of a file within a project. Like [`resolve_language`], the file is read
+/// from `file`, but filename and extension rules are matched against the path relative to `root`,
+/// so that the result does not depend on where the project is located.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language_rel, Language, Scope};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Starlark".to_string(),
+///     scope: Scope::Programming,
+///     filenames: vec!["BUILD".into(), "BUILD.bazel".into()],
+///     ..Default::default()
+/// });
+///
+/// let root = "tests/fixtures/nested";
+/// let lang = resolve_language_rel("tests/fixtures/nested/pkg/BUILD", root, &container).unwrap();
+/// assert_eq!(lang.map(|lang| lang.name.as_str()), Some("Starlark"));
+///
+/// let lang = resolve_language_rel("tests/fixtures/nested/pkg/BUILD.bazel", root, &container);
+/// assert_eq!(lang.unwrap().map(|lang| lang.name.as_str()), Some("Starlark"));
+/// ```
+pub fn resolve_language_rel(
     file: impl AsRef<Path>,
+    root: impl AsRef<Path>,
     container: &impl Container,
 ) -> Result<Option<&Language>, LinguistError> {
-    if is_binary(&file)? {
+    let file = file.as_ref();
+    let relative = file.strip_prefix(root.as_ref()).unwrap_or(file);
+    resolve_language_anchored(file, relative, container, &ResolveOptions::default())
+}
+
+/// Used internally to resolve the [`Language`] of a file whose rules are matched against the given
+/// relative path, see [`count_signals`].
+fn resolve_language_anchored<'a>(
+    file: impl AsRef<Path>,
+    relative: &Path,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Result<Option<&'a Language>, LinguistError> {
+    if let Some(lang) = short_circuit_language(relative, container, options) {
+        return Ok(Some(lang));
+    }
+
+    if check_binary(&file, options)? {
+        if options.detect_binary_formats {
+            return resolve_language_by_magic(&file, container);
+        }
         return Ok(None);
     }
 
-    let mut probabilities: HashMap<String, usize> = HashMap::new();
+    resolve_text_language(&file, relative, container, options)
+}
 
-    if let Ok(candidates) = resolve_languages_by_filename(&file, container) {
-        for candidate in candidates {
-            *probabilities
-                .entry(candidate.name.clone().to_lowercase())
-                .or_insert(1) += 1;
-        }
+/// Used internally to resolve the [`Language`] of a file that is forced by a `linguist-language`
+/// attribute, see [`ResolveOptions::attributes`], or by a unique filename or extension match, if
+/// the respective short circuit is enabled in the given options.
+pub(crate) fn short_circuit_language<'a>(
+    relative: &Path,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Option<&'a Language> {
+    if let Some(lang) = options
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.language(relative))
+        .and_then(|name| container.get_language_by_name(name))
+    {
+        return Some(lang);
     }
 
-    if let Ok(Some(candidate)) = resolve_languages_by_shebang(&file, container) {
-        for lang in candidate {
-            *probabilities
-                .entry(lang.name.clone().to_lowercase())
-                .or_insert(1) += 1;
+    if options.short_circuit_on_filename {
+        let filename = relative.file_name().map(Path::new).unwrap_or(relative);
+        if let Some(lang) = single_language(container.languages_by_filename_iter(filename)) {
+            return Some(lang);
         }
     }
 
-    if let Ok(candidates) = resolve_languages_by_extension(&file, container) {
-        for candidate in candidates {
-            *probabilities
-                .entry(candidate.name.clone().to_lowercase())
-                .or_insert(1) += 1;
-        }
+    if options.short_circuit_on_extension {
+        return single_language(container.languages_by_extension_iter(relative));
     }
 
-    if let Ok(Some(candidate)) = resolve_language_by_content(&file, container) {
-        *probabilities
-            .entry(candidate.name.clone().to_lowercase())
-            .or_insert(1) += 1;
+    None
+}
+
+/// Used internally to resolve the [`Language`] of a text file by weighting all signals, see
+/// [`count_signals`].
+pub(crate) fn resolve_text_language<'a>(
+    file: impl AsRef<Path>,
+    relative: &Path,
+    container: &'a impl Container,
+    options: &ResolveOptions,
+) -> Result<Option<&'a Language>, LinguistError> {
+    let candidates = count_signals(&file, relative, container, options);
+    pick_candidate(&candidates, options)
+}
+
+/// Used internally to pick the most likely of the given weighted candidates, see
+/// [`ResolveOptions::on_ambiguous`].
+fn pick_candidate<'a>(
+    candidates: &[(&'a Language, usize)],
+    options: &ResolveOptions,
+) -> Result<Option<&'a Language>, LinguistError> {
+    let (language, count) = match candidates.first() {
+        Some(&candidate) => candidate,
+        _ => return Err(LinguistError::LanguageNotFound),
+    };
+
+    let is_ambiguous = candidates.get(1).is_some_and(|&(other, other_count)| {
+        other_count == count && preference(other, options) == preference(language, options)
+    });
+    if is_ambiguous && options.on_ambiguous != AmbiguityPolicy::FirstByPriority {
+        return Ok(None);
     }
 
-    let mut ordered: Vec<(&String, &usize)> = probabilities.iter().collect();
-    ordered.sort_by_key(|&(_, v)| v);
-    ordered.reverse();
+    Ok(Some(language))
+}
 
-    if !ordered.is_empty() {
-        return Ok(Some(
-            container
-                .get_language_by_name(ordered.get(0).unwrap().0)
-                .unwrap(),
-        ));
+/// Resolve the [`Language`] of content that is read from the given reader, e.g., from stdin. The
+/// optional name hint is used for the filename and extension signals; without it, the language is
+/// resolved by the shebang and content alone. Only the first [`DEFAULT_MAX_CONTENT_BYTES`] are
+/// read, and binary content resolves to `None`.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language_reader, Language};
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Python".to_string(),
+///     extensions: vec!["py".into()],
+///     interpreters: vec!["python3".into()],
+///     ..Default::default()
+/// });
+///
+/// let lang = resolve_language_reader(None, &b"#!/usr/bin/env python3\nprint(1)\n"[..], &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "Python");
+///
+/// let lang = resolve_language_reader(Some("setup.py"), &b"print(1)\n"[..], &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "Python");
+///
+/// let lang = resolve_language_reader(Some("setup.py"), &b"\x7fELF\x00\x01"[..], &container);
+/// assert_eq!(lang.unwrap(), None);
+/// ```
+pub fn resolve_language_reader<'a, R: Read>(
+    name_hint: Option<&str>,
+    reader: R,
+    container: &'a impl Container,
+) -> Result<Option<&'a Language>, LinguistError> {
+    let mut head = Vec::new();
+    reader
+        .take(DEFAULT_MAX_CONTENT_BYTES as u64)
+        .read_to_end(&mut head)?;
+
+    resolve_head(name_hint.map(Path::new), &head, container)
+}
+
+/// Resolve the [`Language`] of the given in-memory content, e.g., of a blob in a git object
+/// database or an entry of an archive, without touching the filesystem. The name is used for the
+/// filename and extension signals, and the content for the binary check, the shebang and the
+/// content heuristics. Only the first [`DEFAULT_MAX_CONTENT_BYTES`] are scanned, and binary
+/// content resolves to `None`.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{
+///     resolve_language_from_bytes, HeuristicRule, Language, DEFAULT_MAX_CONTENT_BYTES,
+/// };
+///
+/// let mut container = InMemoryLanguageContainer::from_iter([
+///     Language::new("Perl").extensions(["pl", "t"]),
+///     Language::new("Raku").extensions(["raku", "t"]),
+///     Language::new("Python").extensions(["py"]).interpreters(["python3"]),
+/// ]);
+/// container.register_heuristic_rule(HeuristicRule::new(
+///     "Raku".to_string(),
+///     vec!["t".into()],
+///     vec![r"^\s*use\s+v6\b".to_string()],
+/// ));
+///
+/// let content = include_bytes!("../tests/fixtures/ambiguous/plan.t");
+/// let lang = resolve_language_from_bytes("t/plan.t", content, &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "Raku");
+///
+/// // content beyond the first `DEFAULT_MAX_CONTENT_BYTES` is not scanned
+/// let mut late = vec![b'\n'; DEFAULT_MAX_CONTENT_BYTES];
+/// late.extend_from_slice(content);
+/// let lang = resolve_language_from_bytes("t/plan.t", &late, &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "Perl");
+///
+/// let lang = resolve_language_from_bytes("bin/run", b"#!/usr/bin/env python3\n", &container);
+/// assert_eq!(lang.unwrap().unwrap().name, "Python");
+///
+/// let lang = resolve_language_from_bytes("build/main.c", b"\x7fELF\x02\x01\x01\x00", &container);
+/// assert_eq!(lang.unwrap(), None);
+/// ```
+pub fn resolve_language_from_bytes<'a>(
+    name: &str,
+    content: &[u8],
+    container: &'a impl Container,
+) -> Result<Option<&'a Language>, LinguistError> {
+    let head = &content[..content.len().min(DEFAULT_MAX_CONTENT_BYTES)];
+    resolve_head(Some(Path::new(name)), head, container)
+}
+
+/// Used internally to resolve the [`Language`] of the given head of a file with the default
+/// options. Binary content resolves to `None`.
+fn resolve_head<'a>(
+    name: Option<&Path>,
+    head: &[u8],
+    container: &'a impl Container,
+) -> Result<Option<&'a Language>, LinguistError> {
+    if is_binary_bytes(head) {
+        return Ok(None);
     }
-    Err(LinguistError::LanguageNotFound)
+
+    let head = decode_head(head.to_vec());
+    let options = ResolveOptions::default();
+    let candidates = weigh_signals(name, &head, container, &options);
+    pick_candidate(&candidates, &options)
+}
+
+/// Resolve the [`Language`] of the given file. It will try to resolve the language by the modeline,
+/// filename, extension, shebang and content. The most likely language will be returned. A modeline
+/// outweighs all other signals, and an exact filename outweighs the extension, even if the content
+/// agrees with the extension. Equally likely candidates are ordered by popularity and name, see
+/// [`TieBreak::PreferPopular`], so the first of them is returned.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{resolve_language, HeuristicRule, Language};
+///
+/// let mut container = InMemoryLanguageContainer::from_iter([
+///     Language::new("CMake").extensions(["cmake"]).filenames(["CMakeLists.txt"]),
+///     Language::new("Text").extensions(["txt"]),
+/// ]);
+/// container.register_heuristic_rule(HeuristicRule::new(
+///     "Text".to_string(),
+///     vec!["txt".into()],
+///     vec![r"\w+".to_string()],
+/// ));
+///
+/// let lang = resolve_language("tests/fixtures/filenames/CMakeLists.txt", &container).unwrap();
+/// assert_eq!(lang.unwrap().name, "CMake");
+///
+/// // a modeline outweighs all other signals
+/// container.register_language(Language::new("Ruby").extensions(["rb"]));
+/// let lang = resolve_language("tests/fixtures/modelines/tasks.txt", &container).unwrap();
+/// assert_eq!(lang.unwrap().name, "Ruby");
+/// ```
+pub fn resolve_language(
+    file: impl AsRef<Path>,
+    container: &impl Container,
+) -> Result<Option<&Language>, LinguistError> {
+    resolve_language_with(file, container, &ResolveOptions::default())
+}
+
+/// Classifies the given file like [`resolve_language`], but returns `None` whenever the language
+/// cannot be determined instead of distinguishing the reasons. In particular, `None` is returned
+/// if:
+///
+/// - the file is binary or several candidates are tied, i.e., [`resolve_language`] returns
+///   `Ok(None)`,
+/// - no language matches the file at all ([`LinguistError::LanguageNotFound`]),
+/// - the file cannot be found or read ([`LinguistError::FileNotFound`] and
+///   [`LinguistError::IOError`]).
+///
+/// Use [`resolve_language`] if the reason matters.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{classify, Language};
+///
+/// let container = InMemoryLanguageContainer::from_iter([Language::new("C++").extensions(["h"])]);
+///
+/// assert_eq!(classify("tests/fixtures/heuristics/vector.h", &container).unwrap().name, "C++");
+/// assert_eq!(classify("tests/fixtures/missing/vector.h", &container), None);
+/// assert_eq!(classify("tests/fixtures/shebang/no_std.rs", &container), None);
+/// ```
+pub fn classify(file: impl AsRef<Path>, container: &impl Container) -> Option<&Language> {
+    resolve_language(file, container).ok().flatten()
+}
+
+/// Classifies the given in-memory content like [`classify`], see
+/// [`resolve_language_from_bytes`]. The filename, extension, shebang, and content heuristics are
+/// evaluated over the given `name` and `content` alone, without any filesystem access, e.g., to
+/// classify files in a browser through WebAssembly.
+///
+/// # Example
+/// ```
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{classify_bytes, HeuristicRule, Language};
+///
+/// let container = InMemoryLanguageContainer::builder()
+///     .languages([
+///         Language::new("C").extensions(["c"]),
+///         Language::new("MATLAB").extensions(["m"]),
+///         Language::new("Objective-C").extensions(["m"]),
+///         Language::new("Python").interpreters(["python3"]),
+///         Language::new("Makefile").filenames(["Makefile"]),
+///     ])
+///     .heuristic_rules([
+///         HeuristicRule::new("Objective-C".to_string(), vec!["m".into()], vec![r"^\s*@interface\b".to_string()]),
+///         HeuristicRule::new("MATLAB".to_string(), vec!["m".into()], vec![r"^\s*function\b".to_string()]),
+///     ])
+///     .build();
+///
+/// let name = |file, content| classify_bytes(file, content, &container).map(|lang| lang.name.as_str());
+/// assert_eq!(name("Greeter.m", b"@interface Greeter : NSObject\n@end\n"), Some("Objective-C"));
+/// assert_eq!(name("average.m", b"function m = average(x)\nend\n"), Some("MATLAB"));
+/// assert_eq!(name("run", b"#!/usr/bin/env python3\n"), Some("Python"));
+/// assert_eq!(name("Makefile", b"all:\n"), Some("Makefile"));
+/// assert_eq!(name("main.c", b"\x7fELF\x02\x01\x01\x00"), None);
+/// assert_eq!(name("notes", b"plain text\n"), None);
+/// ```
+pub fn classify_bytes<'a>(
+    name: &str,
+    content: &[u8],
+    container: &'a impl Container,
+) -> Option<&'a Language> {
+    resolve_language_from_bytes(name, content, container)
+        .ok()
+        .flatten()
 }
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, ffi::OsString, path::Path};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 
 use serde::Deserialize;
 
@@ -17,6 +21,11 @@ pub struct StaticLanguage<'src> {
     pub interpreters: Option<&'src [&'src str]>,
     pub color: Option<&'src str>,
     pub parent: Option<&'src str>,
+    pub language_id: Option<u64>,
+    pub tm_scope: Option<&'src str>,
+    pub ace_mode: Option<&'src str>,
+    pub codemirror_mode: Option<&'src str>,
+    pub popular: bool,
 }
 
 impl<'src> From<&'src StaticLanguage<'src>> for Language {
@@ -51,8 +60,14 @@ impl<'src> From<&'src StaticLanguage<'src>> for Language {
             scope,
             extensions: extensions.unwrap_or_default(),
             filenames: filenames.unwrap_or_default(),
+            filename_patterns: vec![],
             interpreters: interpreters.unwrap_or_default(),
             color,
+            language_id: value.language_id,
+            tm_scope: value.tm_scope.map(String::from),
+            ace_mode: value.ace_mode.map(String::from),
+            codemirror_mode: value.codemirror_mode.map(String::from),
+            popular: value.popular,
         }
     }
 }
@@ -62,32 +77,81 @@ pub struct StaticHeuristicRule<'a> {
     pub language: &'a str,
     pub extensions: &'a [&'a str],
     pub patterns: &'a [&'a str],
+    pub required_patterns: &'a [&'a str],
 }
 
 impl<'a> From<&'a StaticHeuristicRule<'a>> for HeuristicRule {
     fn from(value: &'a StaticHeuristicRule<'a>) -> Self {
-        Self {
-            language: String::from(value.language),
-            extensions: value
+        let mut rule = Self::new(
+            String::from(value.language),
+            value
                 .extensions
                 .iter()
                 .map(|ext| OsString::from(*ext))
                 .collect(),
-            patterns: value.patterns.iter().map(|&s| String::from(s)).collect(),
-        }
+            value.patterns.iter().map(|&s| String::from(s)).collect(),
+        );
+        rule.required_patterns = value
+            .required_patterns
+            .iter()
+            .map(|&s| String::from(s))
+            .collect();
+        rule
     }
 }
 /// Deserialize a YAML file into a vector of languages. This supports the deserialization of
 /// custom language definition types by taking a generic type parameter. The generic type must
 /// implement the `TryInto<Language>` and the `serde::Deserialize` trait. Furthermore, the path
-/// to the YAML file must be provided.
+/// to the YAML file must be provided. The languages are returned in the order of the file, so
+/// registering them is deterministic.
+///
+/// # Example
+/// ```
+/// use linguist::resolver::Language;
+/// use linguist::serde::deserialize_languages;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Definition {
+///     extensions: Vec<String>,
+/// }
+///
+/// impl TryInto<Language> for Definition {
+///     type Error = ();
+///
+///     fn try_into(self) -> Result<Language, Self::Error> {
+///         Ok(Language {
+///             extensions: self.extensions.into_iter().map(Into::into).collect(),
+///             ..Default::default()
+///         })
+///     }
+/// }
+///
+/// let path = "tests/fixtures/github/languages.yml";
+/// let languages = deserialize_languages::<Definition>(path).unwrap();
+/// let names: Vec<&str> = languages.iter().map(|lang| lang.name.as_str()).collect();
+/// assert_eq!(names[..4], ["C", "C++", "Objective-C", "Rust"]);
+///
+/// for _ in 0..8 {
+///     assert_eq!(deserialize_languages::<Definition>(path).unwrap(), languages);
+/// }
+/// ```
 pub fn deserialize_languages<T>(path: impl AsRef<Path>) -> Result<Vec<Language>, LinguistError>
 where
     for<'de> T: Deserialize<'de>,
     T: TryInto<Language>,
 {
     let content = std::fs::read_to_string(path).unwrap_or_default();
-    let data: HashMap<String, T> = match serde_yaml::from_str(&content) {
+    deserialize_languages_from_str::<T>(&content)
+}
+
+/// Deserialize the given YAML content into a vector of languages, see [`deserialize_languages`].
+pub fn deserialize_languages_from_str<T>(content: &str) -> Result<Vec<Language>, LinguistError>
+where
+    for<'de> T: Deserialize<'de>,
+    T: TryInto<Language>,
+{
+    // a `Mapping` keeps the order of the document, unlike a `HashMap`
+    let data: serde_yaml::Mapping = match serde_yaml::from_str(content) {
         Ok(result) => result,
         Err(_) => {
             return Err(LinguistError::DeserializationError);
@@ -96,6 +160,11 @@ where
 
     let mut languages: Vec<Language> = Vec::new();
     for (name, item) in data.into_iter() {
+        let (name, item) = match (name, serde_yaml::from_value::<T>(item)) {
+            (serde_yaml::Value::String(name), Ok(item)) => (name, item),
+            _ => return Err(LinguistError::DeserializationError),
+        };
+
         match item.try_into() {
             Ok(mut lang) => {
                 lang.name = name;
@@ -110,10 +179,78 @@ where
     Ok(languages)
 }
 
+/// Deserialize all YAML files (`*.yml` and `*.yaml`) of a directory into a vector of languages.
+/// The files are read in alphabetical order; a language that is defined in several files is
+/// overridden by the definition of the later file.
+pub fn deserialize_languages_dir<T>(dir: impl AsRef<Path>) -> Result<Vec<Language>, LinguistError>
+where
+    for<'de> T: Deserialize<'de>,
+    T: TryInto<Language>,
+{
+    let mut languages: Vec<Language> = Vec::new();
+    for path in list_yaml_files(dir)? {
+        for lang in deserialize_languages::<T>(path)? {
+            match languages
+                .iter_mut()
+                .find(|existing| existing.name == lang.name)
+            {
+                Some(existing) => *existing = lang,
+                None => languages.push(lang),
+            }
+        }
+    }
+
+    Ok(languages)
+}
+
+/// Returns all YAML files (`*.yml` and `*.yaml`) of a directory in alphabetical order.
+pub fn list_yaml_files(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, LinguistError> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yml" || ext == "yaml");
+        if is_yaml && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
 /// Deserialize a YAML file into a vector of strings.
 pub fn deserialize_strings(path: impl AsRef<Path>) -> Result<Vec<String>, LinguistError> {
-    let content = std::fs::read_to_string(path).unwrap_or_default();
-    let data: Vec<String> = match serde_yaml::from_str(&content) {
+    let content = std::fs::read_to_string(path)?;
+    deserialize_strings_from_str(&content)
+}
+
+/// Deserialize the given YAML content into a vector of strings.
+pub fn deserialize_strings_from_str(content: &str) -> Result<Vec<String>, LinguistError> {
+    let data: Vec<String> = match serde_yaml::from_str(content) {
+        Ok(result) => result,
+        Err(_) => {
+            return Err(LinguistError::DeserializationError);
+        }
+    };
+
+    Ok(data)
+}
+
+/// Deserialize a YAML file into a map of strings, e.g., `name: url` pairs.
+pub fn deserialize_string_map(
+    path: impl AsRef<Path>,
+) -> Result<HashMap<String, String>, LinguistError> {
+    let content = std::fs::read_to_string(path)?;
+    deserialize_string_map_from_str(&content)
+}
+
+/// Deserialize the given YAML content into a map of strings, see [`deserialize_string_map`].
+pub fn deserialize_string_map_from_str(
+    content: &str,
+) -> Result<HashMap<String, String>, LinguistError> {
+    let data: HashMap<String, String> = match serde_yaml::from_str(content) {
         Ok(result) => result,
         Err(_) => {
             return Err(LinguistError::DeserializationError);
@@ -0,0 +1,229 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::classifier::FilterReason;
+
+/// A `LanguageBreakdown` aggregates the number of bytes per language, e.g., to determine the
+/// language composition of a repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageBreakdown {
+    usages: HashMap<String, u64>,
+    total_size: u64,
+    filtered: HashMap<FilterReason, u64>,
+}
+
+impl LanguageBreakdown {
+    /// Creates a new, empty `LanguageBreakdown`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `size` bytes to the given language.
+    pub fn add_usage(&mut self, lang: &str, size: u64) {
+        *self.usages.entry(lang.to_string()).or_insert(0) += size;
+        self.total_size += size;
+    }
+
+    /// Returns the number of bytes that are attributed to the given language.
+    pub fn usage(&self, lang: &str) -> Option<u64> {
+        self.usages.get(lang).copied()
+    }
+
+    /// Returns the total number of bytes of all languages.
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
+
+    /// Returns the share of the given language in percent.
+    pub fn percentage(&self, lang: &str) -> f64 {
+        match self.usage(lang) {
+            Some(size) if self.total_size > 0 => ((size as f64) * 100.0) / (self.total_size as f64),
+            _ => 0.0,
+        }
+    }
+
+    /// Returns all languages and their sizes, ordered by size in descending order. Languages with
+    /// the same size are ordered by name.
+    pub fn sorted(&self) -> Vec<(&str, u64)> {
+        let mut values: Vec<(&str, u64)> = self
+            .usages
+            .iter()
+            .map(|(lang, size)| (lang.as_str(), *size))
+            .collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        values
+    }
+
+    /// Returns the name of the language with the most bytes.
+    pub fn primary(&self) -> Option<&str> {
+        self.sorted().first().map(|&(lang, _)| lang)
+    }
+
+    /// Compares this breakdown with a previous one, e.g., of an earlier commit, and returns a
+    /// [`LanguageDelta`] for every language that occurs in either of them, ordered by name.
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::stats::LanguageBreakdown;
+    ///
+    /// let mut previous = LanguageBreakdown::new();
+    /// previous.add_usage("Rust", 600);
+    /// previous.add_usage("JavaScript", 200);
+    /// previous.add_usage("Shell", 200);
+    ///
+    /// let mut current = LanguageBreakdown::new();
+    /// current.add_usage("Rust", 900);
+    /// current.add_usage("JavaScript", 200);
+    /// current.add_usage("TypeScript", 100);
+    ///
+    /// let deltas = current.diff(&previous);
+    /// let names: Vec<&str> = deltas.iter().map(|delta| delta.language.as_str()).collect();
+    /// assert_eq!(names, ["JavaScript", "Rust", "Shell", "TypeScript"]);
+    ///
+    /// assert_eq!(deltas[1].size_delta, 300);
+    /// assert_eq!(deltas[1].to_string(), "Rust +15.00%");
+    /// assert!(deltas[2].is_removed());
+    /// assert_eq!(deltas[2].to_string(), "Shell -20.00%");
+    /// assert!(deltas[3].is_added());
+    /// ```
+    pub fn diff(&self, previous: &Self) -> Vec<LanguageDelta> {
+        let mut languages: Vec<&str> = self
+            .usages
+            .keys()
+            .chain(previous.usages.keys())
+            .map(String::as_str)
+            .collect();
+        languages.sort_unstable();
+        languages.dedup();
+
+        languages
+            .into_iter()
+            .map(|lang| {
+                let previous_size = previous.usage(lang).unwrap_or(0);
+                let current_size = self.usage(lang).unwrap_or(0);
+
+                LanguageDelta {
+                    language: lang.to_string(),
+                    previous_size,
+                    current_size,
+                    size_delta: current_size as i64 - previous_size as i64,
+                    percentage_delta: self.percentage(lang) - previous.percentage(lang),
+                }
+            })
+            .collect()
+    }
+
+    /// Adds `size` bytes of a file that is excluded for the given reason. Filtered bytes are kept
+    /// apart from the languages, i.e., they do not count towards [`LanguageBreakdown::total_size`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::classifier::FilterReason;
+    /// use linguist::stats::LanguageBreakdown;
+    ///
+    /// let mut breakdown = LanguageBreakdown::new();
+    /// breakdown.add_usage("Rust", 800);
+    /// breakdown.add_filtered(FilterReason::Vendored, 150);
+    /// breakdown.add_filtered(FilterReason::Generated, 50);
+    ///
+    /// assert_eq!(breakdown.percentage("Rust"), 100.0);
+    /// assert_eq!(breakdown.source_percentage(), 80.0);
+    /// assert_eq!(breakdown.filtered_percentage(FilterReason::Vendored), 15.0);
+    /// assert_eq!(breakdown.filtered_percentage(FilterReason::Generated), 5.0);
+    /// ```
+    pub fn add_filtered(&mut self, reason: FilterReason, size: u64) {
+        *self.filtered.entry(reason).or_insert(0) += size;
+    }
+
+    /// Returns the number of bytes that are excluded for the given reason.
+    pub fn filtered_size(&self, reason: FilterReason) -> Option<u64> {
+        self.filtered.get(&reason).copied()
+    }
+
+    /// Returns the total number of bytes that are excluded for any reason.
+    pub fn total_filtered_size(&self) -> u64 {
+        self.filtered.values().sum()
+    }
+
+    /// Returns all reasons and the number of bytes excluded for them, ordered by size in
+    /// descending order. Reasons with the same size are ordered by name.
+    pub fn sorted_filtered(&self) -> Vec<(FilterReason, u64)> {
+        let mut values: Vec<(FilterReason, u64)> = self
+            .filtered
+            .iter()
+            .map(|(reason, size)| (*reason, *size))
+            .collect();
+        values.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+        values
+    }
+
+    /// Returns the share of the language bytes among all bytes, including the filtered ones, in
+    /// percent.
+    pub fn source_percentage(&self) -> f64 {
+        self.composition_percentage(self.total_size)
+    }
+
+    /// Returns the share of the bytes excluded for the given reason among all bytes, including the
+    /// language bytes, in percent.
+    pub fn filtered_percentage(&self, reason: FilterReason) -> f64 {
+        self.composition_percentage(self.filtered_size(reason).unwrap_or(0))
+    }
+
+    /// Used internally to compute the share of the given size among all bytes in percent.
+    fn composition_percentage(&self, size: u64) -> f64 {
+        match self.total_size + self.total_filtered_size() {
+            0 => 0.0,
+            total => ((size as f64) * 100.0) / (total as f64),
+        }
+    }
+
+    /// Returns `true` if no usage has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.usages.is_empty()
+    }
+}
+
+impl Display for LanguageBreakdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (lang, size) in self.sorted() {
+            writeln!(f, "{:-6.2}% {:-7}   {}", self.percentage(lang), size, lang)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A `LanguageDelta` describes how the usage of a language changed between two
+/// [`LanguageBreakdown`]s, see [`LanguageBreakdown::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageDelta {
+    pub language: String,
+    /// The number of bytes in the previous breakdown.
+    pub previous_size: u64,
+    /// The number of bytes in the current breakdown.
+    pub current_size: u64,
+    /// The change of the number of bytes.
+    pub size_delta: i64,
+    /// The change of the share in percentage points.
+    pub percentage_delta: f64,
+}
+
+impl LanguageDelta {
+    /// Returns `true` if the language does not occur in the previous breakdown.
+    pub fn is_added(&self) -> bool {
+        self.previous_size == 0 && self.current_size > 0
+    }
+
+    /// Returns `true` if the language does not occur in the current breakdown.
+    pub fn is_removed(&self) -> bool {
+        self.previous_size > 0 && self.current_size == 0
+    }
+}
+
+impl Display for LanguageDelta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:+.2}%", self.language, self.percentage_delta)
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use tokio::{
+    io::AsyncReadExt,
+    sync::{
+        mpsc::{self, Receiver},
+        Semaphore,
+    },
+};
+
+use crate::{
+    classifier::Filters,
+    container::Container,
+    error::LinguistError,
+    resolver::{resolve_language_from_bytes, Language, DEFAULT_MAX_CONTENT_BYTES},
+    utils::relative_path,
+};
+
+/// The number of resolved files that are buffered before the directory walk waits for the
+/// receiver.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// The number of files that are read and resolved at the same time.
+const CONCURRENT_FILES: usize = 16;
+
+/// A `ResolvedFiles` stream yields the files of a directory together with their [`Language`], see
+/// [`resolve_directory`]. Errors, e.g., of a directory that cannot be read, are yielded as well,
+/// and the walk continues with the next entry.
+#[derive(Debug)]
+pub struct ResolvedFiles {
+    receiver: Receiver<Result<(PathBuf, Language), LinguistError>>,
+}
+
+impl ResolvedFiles {
+    /// Returns the next resolved file, or `None` once the whole directory has been walked.
+    pub async fn recv(&mut self) -> Option<Result<(PathBuf, Language), LinguistError>> {
+        self.receiver.recv().await
+    }
+}
+
+impl Stream for ResolvedFiles {
+    type Item = Result<(PathBuf, Language), LinguistError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// Resolves the languages of all files within the given directory without blocking the async
+/// runtime. The directory is walked and the beginning of each file, at most
+/// [`DEFAULT_MAX_CONTENT_BYTES`], is read with `tokio::fs`. Files are run through the given
+/// [`Filters`] first, and only the resolution of the content, see
+/// [`resolve_language_from_bytes`], is moved to the blocking thread pool via `spawn_blocking`.
+/// Several files are resolved concurrently, so they are yielded in no particular order.
+///
+/// The returned [`ResolvedFiles`] stream yields every resolved file together with its
+/// [`Language`]; files that are filtered or whose language cannot be determined are skipped. This
+/// function must be called from within a tokio runtime.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use linguist::classifier::Filters;
+/// use linguist::container::InMemoryLanguageContainer;
+/// use linguist::resolver::{Language, Scope};
+/// use linguist::tokio::resolve_directory;
+///
+/// let mut container = InMemoryLanguageContainer::default();
+/// container.register_language(Language {
+///     name: "Rust".to_string(),
+///     scope: Scope::Programming,
+///     extensions: vec!["rs".into()],
+///     ..Default::default()
+/// });
+/// let container = Arc::new(container);
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let root = "tests/fixtures/vendored-js";
+///     let mut files = resolve_directory(root, container.clone(), Filters::new());
+///
+///     let (path, lang) = files.recv().await.unwrap().unwrap();
+///     assert!(path.ends_with("src/main.rs"));
+///     assert_eq!(lang.name, "Rust");
+///     assert!(files.recv().await.is_none());
+///
+///     let mut files = resolve_directory("tests/fixtures/missing", container, Filters::new());
+///     assert!(files.recv().await.unwrap().is_err());
+///     assert!(files.recv().await.is_none());
+/// });
+/// ```
+pub fn resolve_directory<C>(
+    root: impl Into<PathBuf>,
+    container: Arc<C>,
+    filters: Filters,
+) -> ResolvedFiles
+where
+    C: Container + Send + Sync + 'static,
+{
+    let root = root.into();
+    let filters = Arc::new(filters);
+    let permits = Arc::new(Semaphore::new(CONCURRENT_FILES));
+    let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut directories = vec![root.clone()];
+
+        while let Some(directory) = directories.pop() {
+            let mut entries = match tokio::fs::read_dir(&directory).await {
+                Ok(entries) => entries,
+                Err(err) => {
+                    if sender.send(Err(err.into())).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+            };
+
+            loop {
+                let entry = match entries.next_entry().await {
+                    Ok(Some(entry)) => entry,
+                    Ok(None) => break,
+                    Err(err) => {
+                        if sender.send(Err(err.into())).await.is_err() {
+                            return;
+                        }
+                        break;
+                    }
+                };
+
+                let file_type = match entry.file_type().await {
+                    Ok(file_type) => file_type,
+                    Err(err) => {
+                        if sender.send(Err(err.into())).await.is_err() {
+                            return;
+                        }
+                        continue;
+                    }
+                };
+
+                let path = entry.path();
+                if file_type.is_dir() {
+                    if entry.file_name() != ".git" {
+                        directories.push(path);
+                    }
+                    continue;
+                }
+
+                if !file_type.is_file() {
+                    continue;
+                }
+
+                let relative = relative_path(&path, &root).to_path_buf();
+                if filters.check(&relative).is_some() {
+                    continue;
+                }
+
+                let permit = match Arc::clone(&permits).acquire_owned().await {
+                    Ok(permit) => permit,
+                    _ => return,
+                };
+                let container = Arc::clone(&container);
+                let filters = Arc::clone(&filters);
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let item = match resolve_file(&path, relative, container, &filters).await {
+                        Ok(Some(language)) => Ok((path, language)),
+                        Ok(None) => return,
+                        Err(err) => Err(err),
+                    };
+                    let _ = sender.send(item).await;
+                    drop(permit);
+                });
+            }
+        }
+    });
+
+    ResolvedFiles { receiver }
+}
+
+/// Used internally to read the beginning of the given file asynchronously and resolve its
+/// [`Language`] on the blocking thread pool. Filtered files and files whose language cannot be
+/// determined resolve to `None`.
+async fn resolve_file<C>(
+    file: &Path,
+    relative: PathBuf,
+    container: Arc<C>,
+    filters: &Filters,
+) -> Result<Option<Language>, LinguistError>
+where
+    C: Container + Send + Sync + 'static,
+{
+    let mut head = Vec::new();
+    tokio::fs::File::open(file)
+        .await?
+        .take(DEFAULT_MAX_CONTENT_BYTES as u64)
+        .read_to_end(&mut head)
+        .await?;
+
+    if filters.check_head(&relative, &head).is_some() {
+        return Ok(None);
+    }
+
+    let language = tokio::task::spawn_blocking(move || {
+        let name = relative.to_string_lossy();
+        resolve_language_from_bytes(&name, &head, container.as_ref()).map(|lang| lang.cloned())
+    })
+    .await
+    .map_err(std::io::Error::from)?;
+
+    match language {
+        Err(LinguistError::LanguageNotFound) => Ok(None),
+        language => language,
+    }
+}
@@ -1,4 +1,6 @@
 use std::{
+    borrow::Cow,
+    ffi::OsString,
     io::{BufRead, Cursor, Read},
     path::Path,
 };
@@ -11,7 +13,7 @@ pub static CONFIGURATION_EXTENSIONS: [&str; 6] = ["xml", "json", "toml", "yaml",
 /// Checks if a file is a configuration file by checking if it has a configuration extension.
 ///
 /// # Example
-/// ```should_panic
+/// ```
 /// use std::path::PathBuf;
 /// use linguist::utils::is_configuration;
 ///
@@ -19,28 +21,223 @@ pub static CONFIGURATION_EXTENSIONS: [&str; 6] = ["xml", "json", "toml", "yaml",
 /// assert!(is_configuration(path));
 /// ```
 pub fn is_configuration(file: impl AsRef<Path>) -> bool {
-    if let Some(ext) = file.as_ref().to_path_buf().extension() {
-        return CONFIGURATION_EXTENSIONS.contains(&ext.to_str().unwrap());
+    is_configuration_with(file, &CONFIGURATION_EXTENSIONS)
+}
+
+/// Checks if a file is a configuration file like [`is_configuration`], but with the given
+/// extensions instead of [`CONFIGURATION_EXTENSIONS`]. Extensions may be given with or without
+/// the leading `.` and are compared ignoring ASCII case.
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_configuration_with;
+///
+/// let extensions = [".cfg", "conf", "properties", "env"];
+/// assert!(is_configuration_with("setup.cfg", &extensions));
+/// assert!(is_configuration_with("nginx/site.CONF", &extensions));
+/// assert!(!is_configuration_with("schema.sql", &extensions));
+/// ```
+pub fn is_configuration_with(file: impl AsRef<Path>, extensions: &[&str]) -> bool {
+    has_any_extension(file.as_ref(), extensions)
+}
+
+/// Normalizes an extension as it is listed by GitHub's Linguist, e.g., `.rb`, into the form that
+/// is compared with [`Path::extension`], i.e., without the leading `.`. Exactly one leading `.` is
+/// removed, so extensions with several parts keep their interior dots.
+///
+/// # Example
+/// ```
+/// use linguist::utils::normalize_extension;
+///
+/// assert_eq!(normalize_extension(".d.ts"), "d.ts");
+/// assert_eq!(normalize_extension(".tar.gz"), "tar.gz");
+/// assert_eq!(normalize_extension("rb"), "rb");
+/// assert_eq!(normalize_extension("rb.erb"), "rb.erb");
+/// ```
+pub fn normalize_extension(ext: &str) -> OsString {
+    OsString::from(ext.strip_prefix('.').unwrap_or(ext))
+}
+
+/// Checks whether the given filename matches a glob `pattern`, e.g., `Dockerfile.*`. A `*`
+/// matches any sequence of characters and a `?` matches a single character; all other characters
+/// are compared exactly.
+///
+/// # Example
+/// ```
+/// use linguist::utils::glob_match;
+///
+/// assert!(glob_match("Dockerfile.*", "Dockerfile.dev"));
+/// assert!(glob_match("Jenkinsfile*", "Jenkinsfile"));
+/// assert!(glob_match(".babelrc.*", ".babelrc.json"));
+/// assert!(glob_match("?akefile", "Rakefile"));
+/// assert!(!glob_match("Dockerfile.*", "Dockerfile"));
+/// assert!(!glob_match("*.json", "package.json5"));
+/// ```
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // the position of the last `*` and the position in the name it has matched up to
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // let the last `*` match one more character
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Extensions of image files.
+pub static IMAGE_EXTENSIONS: [&str; 11] = [
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "bmp", "ico", "tif", "tiff", "avif",
+];
+
+/// Extensions of audio and video files.
+pub static MEDIA_EXTENSIONS: [&str; 13] = [
+    "mp3", "wav", "ogg", "flac", "aac", "m4a", "mp4", "m4v", "mov", "avi", "mkv", "webm", "wmv",
+];
+
+/// Checks if a file is an image by checking if it has one of the [`IMAGE_EXTENSIONS`], ignoring
+/// ASCII case. Unlike [`is_binary`], the content is not read.
+///
+/// SVG files are images, even though they are XML and may be counted as markup. Callers that want
+/// to treat them as markup have to check for the `svg` extension themselves.
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_image;
+///
+/// assert!(is_image("assets/logo.PNG"));
+/// assert!(is_image("assets/logo.svg"));
+/// assert!(!is_image("assets/intro.mp4"));
+/// ```
+pub fn is_image(file: impl AsRef<Path>) -> bool {
+    has_any_extension(file.as_ref(), &IMAGE_EXTENSIONS)
+}
+
+/// Checks if a file is an audio or video file by checking if it has one of the
+/// [`MEDIA_EXTENSIONS`], ignoring ASCII case. Images are not media, see [`is_image`].
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_media;
+///
+/// assert!(is_media("assets/intro.mp4"));
+/// assert!(is_media("sounds/click.WAV"));
+/// assert!(!is_media("assets/logo.png"));
+/// ```
+pub fn is_media(file: impl AsRef<Path>) -> bool {
+    has_any_extension(file.as_ref(), &MEDIA_EXTENSIONS)
+}
+
+/// Used internally to check whether the given file has one of the given extensions, which may be
+/// given with or without the leading `.`, ignoring ASCII case.
+fn has_any_extension(file: &Path, extensions: &[&str]) -> bool {
+    match file.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => extensions
+            .iter()
+            .any(|candidate| candidate.trim_start_matches('.').eq_ignore_ascii_case(ext)),
+        None => false,
     }
-    false
 }
 
 /// Checks if a file is documentation by checking if it matches any of the documentation rules.
 ///
 /// # Example
-/// ```should_panic
+/// ```
 /// use std::path::PathBuf;
 /// use regex::RegexSet;
 /// use linguist::utils::is_documentation;
 ///
-/// let rules = RegexSet::new(linguist::utils::DOCS).unwrap();
-/// let path = PathBuf::from("/foo/docs");
+/// let rules = RegexSet::new([r"^docs?/"]).unwrap();
+/// let path = PathBuf::from("docs/index.md");
 /// assert!(is_documentation(path, &rules));
 /// ```
 pub fn is_documentation(file: impl AsRef<Path>, matcher: &RegexSet) -> bool {
     matcher.is_match(file.as_ref().display().to_string().as_str())
 }
 
+/// Returns the first of the documentation rules that matches the given file, e.g., to explain why
+/// it is excluded, see [`is_documentation`].
+///
+/// # Example
+/// ```
+/// use regex::RegexSet;
+/// use linguist::utils::matching_documentation_rule;
+///
+/// let rules = RegexSet::new([r"^docs?/", r"(^|/)README(\.|$)"]).unwrap();
+/// assert_eq!(matching_documentation_rule("docs/README.md", &rules), Some(r"^docs?/"));
+/// assert_eq!(matching_documentation_rule("src/README", &rules), Some(r"(^|/)README(\.|$)"));
+/// assert_eq!(matching_documentation_rule("src/main.rs", &rules), None);
+/// ```
+pub fn matching_documentation_rule(file: impl AsRef<Path>, matcher: &RegexSet) -> Option<&str> {
+    let file = file.as_ref().display().to_string();
+    first_matching_rule(&file, matcher)
+}
+
+/// A `DocumentationMatcher` compiles the documentation rules, e.g., of GitHub's
+/// `documentation.yml`, once, so they can be checked against many files, see
+/// [`is_documentation`].
+///
+/// # Example
+/// ```
+/// use linguist::utils::DocumentationMatcher;
+///
+/// let matcher = DocumentationMatcher::new([r"^docs?/", r"(^|/)README(\.|$)"]).unwrap();
+/// assert!(matcher.is_match("docs/index.md"));
+/// assert!(matcher.is_match("crates/core/README.md"));
+/// assert!(!matcher.is_match("src/docs.rs"));
+///
+/// assert!(DocumentationMatcher::new(["docs/("]).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DocumentationMatcher {
+    rules: RegexSet,
+}
+
+impl DocumentationMatcher {
+    /// Compiles the given documentation rules. Returns a [`LinguistError::PatternCompileError`]
+    /// if one of them is not a valid pattern.
+    pub fn new<S: AsRef<str>>(rules: impl IntoIterator<Item = S>) -> Result<Self, LinguistError> {
+        Ok(DocumentationMatcher {
+            rules: RegexSet::new(rules)?,
+        })
+    }
+
+    /// Checks whether the given file is documentation, see [`is_documentation`].
+    pub fn is_match(&self, file: impl AsRef<Path>) -> bool {
+        is_documentation(file, &self.rules)
+    }
+
+    /// Returns the first rule that matches the given file, see [`matching_documentation_rule`].
+    pub fn matching_rule(&self, file: impl AsRef<Path>) -> Option<&str> {
+        matching_documentation_rule(file, &self.rules)
+    }
+}
+
+impl From<DocumentationMatcher> for RegexSet {
+    fn from(value: DocumentationMatcher) -> Self {
+        value.rules
+    }
+}
+
 /// Checks if a file is a dotfile by checking if it starts with a dot.
 ///
 /// # Examples
@@ -63,46 +260,494 @@ pub fn is_dotfile(file: impl AsRef<Path>) -> bool {
     file.as_ref().display().to_string().starts_with('.')
 }
 
-/// Checks if a file is a vendor file by checking if it matches any of the vendor rules.
+/// Checks if a file is a vendor file by checking if it matches any of the vendor rules. The rules
+/// of GitHub's `vendor.yml` are anchored to the root of the repository, so the path is expected
+/// to be relative to it, see [`relative_path`].
+///
+/// # Example
+/// ```
+/// use regex::RegexSet;
+/// use linguist::utils::{is_vendor, relative_path};
+///
+/// let rules = RegexSet::new([r"^node_modules/"]).unwrap();
+/// assert!(is_vendor("node_modules/react/index.js", &rules));
+/// assert!(!is_vendor("/home/dev/app/node_modules/react/index.js", &rules));
+///
+/// let file = relative_path("/home/dev/app/node_modules/react/index.js", "/home/dev/app");
+/// assert!(is_vendor(file, &rules));
+/// ```
 pub fn is_vendor(file: impl AsRef<Path>, matcher: &RegexSet) -> bool {
     matcher.is_match(file.as_ref().to_str().unwrap_or(""))
 }
 
-const FIRST_FEW_BYTES: usize = 8000;
+/// Returns the first of the vendor rules that matches the given file, e.g., to explain why it is
+/// excluded, see [`is_vendor`].
+///
+/// # Example
+/// ```
+/// use regex::RegexSet;
+/// use linguist::utils::matching_vendor_rule;
+///
+/// let rules = RegexSet::new([r"(^|/)vendor/", r"^node_modules/", r"\.min\.js$"]).unwrap();
+/// assert_eq!(matching_vendor_rule("node_modules/jquery.min.js", &rules), Some(r"^node_modules/"));
+/// assert_eq!(matching_vendor_rule("src/vendor/lib.c", &rules), Some(r"(^|/)vendor/"));
+/// assert_eq!(matching_vendor_rule("src/main.rs", &rules), None);
+/// ```
+pub fn matching_vendor_rule(file: impl AsRef<Path>, matcher: &RegexSet) -> Option<&str> {
+    first_matching_rule(file.as_ref().to_str().unwrap_or(""), matcher)
+}
+
+/// Used internally to return the pattern of the first rule of the given set that matches the given
+/// path.
+fn first_matching_rule<'a>(path: &str, matcher: &'a RegexSet) -> Option<&'a str> {
+    let index = matcher.matches(path).into_iter().next()?;
+    Some(matcher.patterns()[index].as_str())
+}
+
+/// A `VendorMatcher` compiles the vendor rules, e.g., of GitHub's `vendor.yml`, once, so they can
+/// be checked against many files, see [`is_vendor`].
+///
+/// # Example
+/// ```
+/// use linguist::classifier::Filters;
+/// use linguist::utils::VendorMatcher;
+///
+/// let matcher = VendorMatcher::new([r"^node_modules/", r"(^|/)vendor/"]).unwrap();
+/// assert!(matcher.is_match("node_modules/react/index.js"));
+/// assert!(matcher.is_match("third_party/vendor/lib.c"));
+/// assert!(!matcher.is_match("src/vendors.rs"));
+///
+/// // the compiled rules can be handed to the filters as well
+/// let filters = Filters::new().vendors(matcher);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VendorMatcher {
+    rules: RegexSet,
+}
+
+impl VendorMatcher {
+    /// Compiles the given vendor rules. Returns a [`LinguistError::PatternCompileError`] if one of
+    /// them is not a valid pattern.
+    pub fn new<S: AsRef<str>>(rules: impl IntoIterator<Item = S>) -> Result<Self, LinguistError> {
+        Ok(VendorMatcher {
+            rules: RegexSet::new(rules)?,
+        })
+    }
+
+    /// Checks whether the given file is vendored, see [`is_vendor`]. Like the rules of GitHub's
+    /// `vendor.yml`, the path is expected to be relative to the root of the repository.
+    pub fn is_match(&self, file: impl AsRef<Path>) -> bool {
+        is_vendor(file, &self.rules)
+    }
+
+    /// Returns the first rule that matches the given file, see [`matching_vendor_rule`].
+    ///
+    /// # Example
+    /// ```
+    /// use linguist::utils::VendorMatcher;
+    ///
+    /// let matcher = VendorMatcher::new([r"^node_modules/", r"(^|/)vendor/"]).unwrap();
+    /// assert_eq!(matcher.matching_rule("vendor/lib.c"), Some(r"(^|/)vendor/"));
+    /// assert_eq!(matcher.matching_rule("src/lib.c"), None);
+    /// ```
+    pub fn matching_rule(&self, file: impl AsRef<Path>) -> Option<&str> {
+        matching_vendor_rule(file, &self.rules)
+    }
+}
+
+impl From<VendorMatcher> for RegexSet {
+    fn from(value: VendorMatcher) -> Self {
+        value.rules
+    }
+}
+
+/// Returns the path of the given file relative to the given root, e.g., of the repository, as it
+/// is expected by [`is_vendor`] and [`is_documentation`]. A leading `./` is removed, and a file
+/// outside of the root is returned unchanged.
+///
+/// # Example
+/// ```
+/// use std::path::Path;
+/// use linguist::utils::relative_path;
+///
+/// assert_eq!(relative_path("/repo/src/main.rs", "/repo"), Path::new("src/main.rs"));
+/// assert_eq!(relative_path("./src/main.rs", "/repo"), Path::new("src/main.rs"));
+/// assert_eq!(relative_path("/other/main.rs", "/repo"), Path::new("/other/main.rs"));
+/// ```
+pub fn relative_path(file: &(impl AsRef<Path> + ?Sized), root: impl AsRef<Path>) -> &Path {
+    let file = file.as_ref();
+    let file = file.strip_prefix(root).unwrap_or(file);
+    file.strip_prefix(".").unwrap_or(file)
+}
+
+pub(crate) const FIRST_FEW_BYTES: usize = 8000;
 
 /// Checks if a file is binary or not by checking if it contains a null byte.
 /// this is based on <https://git.kernel.org/pub/scm/git/git.git/tree/xdiff-interface.c?id=HEAD#n198>
 pub fn is_binary(path: impl AsRef<Path>) -> Result<bool, LinguistError> {
-    let mut file = std::fs::File::open(path.as_ref())?;
-    let mut buf = [0; FIRST_FEW_BYTES];
-    let n = file.read(&mut buf)?;
+    let file = std::fs::File::open(path.as_ref())?;
+    Ok(is_binary_reader(file)?)
+}
 
-    for byte in &buf[..n] {
-        if *byte == 0 {
-            return Ok(true);
-        }
+/// Checks if the content of the given reader is binary, like [`is_binary`] does for a file. At
+/// most the first few bytes are read, so the reader can be, e.g., a large git blob.
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_binary_reader;
+///
+/// assert!(is_binary_reader(&b"\x7fELF\x02\x01\x01\x00"[..]).unwrap());
+/// assert!(!is_binary_reader(&b"fn main() {}\n"[..]).unwrap());
+/// ```
+pub fn is_binary_reader(reader: impl Read) -> std::io::Result<bool> {
+    let mut head = Vec::with_capacity(FIRST_FEW_BYTES);
+    reader.take(FIRST_FEW_BYTES as u64).read_to_end(&mut head)?;
+
+    Ok(is_binary_bytes(&head))
+}
+
+/// Checks if the given bytes are binary, like [`is_binary`] does for a file, i.e., whether their
+/// first few bytes contain a null byte. UTF-16 and UTF-32 text that starts with a byte order mark
+/// is not binary, although it contains null bytes, see [`detect_encoding`].
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_binary_bytes;
+///
+/// assert!(is_binary_bytes(b"\x7fELF\x02\x01\x01\x00"));
+/// assert!(!is_binary_bytes(b"fn main() {}\n"));
+/// assert!(!is_binary_bytes(b"\xff\xfef\x00n\x00 \x00m\x00a\x00i\x00n\x00"));
+/// ```
+pub fn is_binary_bytes(data: &[u8]) -> bool {
+    if detect_encoding(data) != Encoding::Utf8 {
+        return false;
     }
-    Ok(false)
+
+    data.iter().take(FIRST_FEW_BYTES).any(|&byte| byte == 0)
+}
+
+/// The `Encoding` of a text as indicated by its byte order mark, see [`detect_encoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, with or without a byte order mark.
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+/// Byte order marks and the encoding they indicate. The UTF-32 little-endian mark starts with the
+/// UTF-16 little-endian one, so it is checked first.
+static BYTE_ORDER_MARKS: [(&[u8], Encoding); 5] = [
+    (b"\xff\xfe\x00\x00", Encoding::Utf32Le),
+    (b"\x00\x00\xfe\xff", Encoding::Utf32Be),
+    (b"\xff\xfe", Encoding::Utf16Le),
+    (b"\xfe\xff", Encoding::Utf16Be),
+    (b"\xef\xbb\xbf", Encoding::Utf8),
+];
+
+/// Detects the encoding of the given text by its byte order mark (BOM). Text without a byte order
+/// mark is treated as UTF-8.
+///
+/// # Example
+/// ```
+/// use linguist::utils::{detect_encoding, Encoding};
+///
+/// assert_eq!(detect_encoding(b"\xff\xfeu\x00s\x00e\x00"), Encoding::Utf16Le);
+/// assert_eq!(detect_encoding(b"\xff\xfe\x00\x00u\x00\x00\x00"), Encoding::Utf32Le);
+/// assert_eq!(detect_encoding(b"\xef\xbb\xbfuse"), Encoding::Utf8);
+/// assert_eq!(detect_encoding(b"use"), Encoding::Utf8);
+/// ```
+pub fn detect_encoding(data: &[u8]) -> Encoding {
+    byte_order_mark(data).map_or(Encoding::Utf8, |(_, encoding)| encoding)
+}
+
+/// Used internally to determine the byte order mark at the beginning of the given text.
+fn byte_order_mark(data: &[u8]) -> Option<(&'static [u8], Encoding)> {
+    BYTE_ORDER_MARKS
+        .iter()
+        .copied()
+        .find(|(bom, _)| data.starts_with(bom))
+}
+
+/// Decodes the given text by its encoding, see [`detect_encoding`], and strips the byte order
+/// mark. Invalid or incomplete characters, e.g., at the end of a truncated file, are replaced by
+/// `U+FFFD`.
+///
+/// # Example
+/// ```
+/// use linguist::utils::decode_text;
+///
+/// assert_eq!(decode_text(b"\xfe\xff\x00u\x00s\x00e"), "use");
+/// assert_eq!(decode_text(b"\xef\xbb\xbfuse"), "use");
+/// assert_eq!(decode_text(b"use"), "use");
+/// ```
+pub fn decode_text(data: &[u8]) -> Cow<'_, str> {
+    let (encoding, content) = match byte_order_mark(data) {
+        Some((bom, encoding)) => (encoding, &data[bom.len()..]),
+        _ => (Encoding::Utf8, data),
+    };
+
+    let utf16 = |unit: fn([u8; 2]) -> u16| -> Cow<'_, str> {
+        let units = content.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    };
+    let utf32 = |unit: fn([u8; 4]) -> u32| -> Cow<'_, str> {
+        content
+            .chunks_exact(4)
+            .map(|quad| {
+                char::from_u32(unit([quad[0], quad[1], quad[2], quad[3]]))
+                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+            })
+            .collect()
+    };
+
+    match encoding {
+        Encoding::Utf8 => String::from_utf8_lossy(content),
+        Encoding::Utf16Le => utf16(u16::from_le_bytes),
+        Encoding::Utf16Be => utf16(u16::from_be_bytes),
+        Encoding::Utf32Le => utf32(u32::from_le_bytes),
+        Encoding::Utf32Be => utf32(u32::from_be_bytes),
+    }
+}
+
+/// Leading-byte signatures of common binary formats and the name of the respective format.
+pub static MAGIC_NUMBERS: [(&[u8], &str); 12] = [
+    (b"%PDF-", "PDF"),
+    (b"\x89PNG\r\n\x1a\n", "PNG"),
+    (b"GIF87a", "GIF"),
+    (b"GIF89a", "GIF"),
+    (b"\xff\xd8\xff", "JPEG"),
+    (b"PK\x03\x04", "ZIP"),
+    (b"\x1f\x8b", "Gzip"),
+    (b"BZh", "Bzip2"),
+    (b"\xfd7zXZ\x00", "XZ"),
+    (b"7z\xbc\xaf\x27\x1c", "7-Zip"),
+    (b"\x7fELF", "ELF"),
+    (b"\x00asm", "WebAssembly"),
+];
+
+/// Detects the format of binary data by its leading bytes, similar to `file(1)`, and returns the
+/// name of the detected format.
+///
+/// # Example
+/// ```
+/// use linguist::utils::detect_magic;
+///
+/// assert_eq!(detect_magic(b"%PDF-1.7\n"), Some("PDF"));
+/// assert_eq!(detect_magic(b"fn main() {}"), None);
+/// ```
+pub fn detect_magic(data: &[u8]) -> Option<&'static str> {
+    MAGIC_NUMBERS
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+        .map(|&(_, name)| name)
+}
+
+/// Markers that identify the language of a file if its first line starts with them, e.g.,
+/// cloud-init's `#cloud-config`, and the name of the respective language.
+pub static FIRST_LINE_MARKERS: [(&str, &str); 4] = [
+    ("#cloud-config", "YAML"),
+    ("%YAML", "YAML"),
+    ("#%RAML", "RAML"),
+    ("// swift-tools-version:", "Swift"),
+];
+
+/// Detects the language of a file by a marker at the beginning of its first line and returns the
+/// name of the detected language. Unlike a shebang, a marker does not name an interpreter.
+///
+/// # Example
+/// ```
+/// use linguist::utils::detect_first_line_marker;
+///
+/// assert_eq!(detect_first_line_marker("#cloud-config"), Some("YAML"));
+/// assert_eq!(detect_first_line_marker("// swift-tools-version: 5.9"), Some("Swift"));
+/// assert_eq!(detect_first_line_marker("# cloud-config"), None);
+/// ```
+pub fn detect_first_line_marker(line: &str) -> Option<&'static str> {
+    FIRST_LINE_MARKERS
+        .iter()
+        .find(|(marker, _)| line.starts_with(marker))
+        .map(|&(_, name)| name)
+}
+
+/// The header of a Git LFS pointer file, see <https://github.com/git-lfs/git-lfs/blob/main/docs/spec.md>.
+pub static LFS_POINTER_HEADER: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+
+/// Checks if the given content is a Git LFS pointer, i.e., a small text file that is stored in
+/// place of a large file.
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_lfs_pointer;
+///
+/// let pointer = b"version https://git-lfs.github.com/spec/v1
+/// oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+/// size 12345
+/// ";
+/// assert!(is_lfs_pointer(pointer));
+/// assert!(!is_lfs_pointer(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"));
+/// ```
+pub fn is_lfs_pointer(data: &[u8]) -> bool {
+    data.starts_with(LFS_POINTER_HEADER)
 }
 
 pub static GENERATED_NAMES_EXTENSIONS: [&str; 3] = ["nib", "xcworkspacedata", "xcuserstate"];
 
-/// Checks if a file is generated by checking it's extension.
-pub fn is_generated(file: impl AsRef<Path>) -> bool {
-    if let Some(ext) = file.as_ref().to_path_buf().extension() {
-        return GENERATED_NAMES_EXTENSIONS.contains(&ext.to_str().unwrap());
+/// Names of files that are generated by package managers, e.g., lock files.
+pub static GENERATED_FILENAMES: [&str; 11] = [
+    "Cargo.lock",
+    "composer.lock",
+    "go.sum",
+    "Gopkg.lock",
+    "npm-shrinkwrap.json",
+    "package-lock.json",
+    "Package.resolved",
+    "Pipfile.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "yarn.lock",
+];
+
+/// Endings of the names of generated files, e.g., minified assets, source maps, and code that is
+/// generated by the protocol buffer compiler.
+pub static GENERATED_SUFFIXES: [&str; 10] = [
+    ".min.js",
+    ".min.css",
+    ".js.map",
+    ".css.map",
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".pb.swift",
+];
+
+/// Markers within the first [`GENERATED_SEARCH_LINES`] lines of a file that declare the file as
+/// generated, e.g., by Go tools, the protocol buffer compiler, or parser generators.
+pub static GENERATED_MARKERS: [&str; 5] = [
+    "DO NOT EDIT",
+    "@generated",
+    "Generated by the protocol buffer compiler",
+    "A Bison parser, made by GNU Bison",
+    "by ANTLR",
+];
+
+/// The number of lines at the beginning of a file that are scanned for the [`GENERATED_MARKERS`].
+pub const GENERATED_SEARCH_LINES: usize = 5;
+
+/// The average length of the lines of a JavaScript or CSS file above which it is considered to be
+/// minified, like in GitHub's Linguist.
+pub const MINIFIED_LINE_LENGTH: usize = 110;
+
+/// Checks if a file is generated, following a subset of the rules of GitHub's Linguist. The path
+/// is checked against [`GENERATED_NAMES_EXTENSIONS`], [`GENERATED_FILENAMES`], and
+/// [`GENERATED_SUFFIXES`]. If the content of the file is given, it is also checked for
+/// [`GENERATED_MARKERS`], for minified JavaScript or CSS, see [`MINIFIED_LINE_LENGTH`], and for
+/// source maps, including JavaScript or CSS that ends with a `sourceMappingURL` comment.
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_generated;
+///
+/// assert!(is_generated("Cargo.lock", None));
+/// assert!(is_generated("dist/app.min.js", None));
+/// assert!(is_generated("api/service.pb.go", None));
+/// assert!(!is_generated("src/app.js", None));
+///
+/// let minified = format!("var a={};", "1,".repeat(100));
+/// assert!(is_generated("src/app.js", Some(minified.as_bytes())));
+/// assert!(is_generated("src/app.js", Some(b"var a = 1;\n//# sourceMappingURL=app.js.map\n")));
+/// assert!(is_generated("src/parser.c", Some(b"/* A Bison parser, made by GNU Bison 3.8.2.  */\n")));
+/// assert!(!is_generated("src/app.js", Some(b"var a = 1;\n")));
+/// ```
+pub fn is_generated(file: impl AsRef<Path>, content: Option<&[u8]>) -> bool {
+    let file = file.as_ref();
+    let name = match file.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name,
+        _ => return false,
+    };
+
+    let ext = file.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if GENERATED_NAMES_EXTENSIONS.contains(&ext)
+        || GENERATED_FILENAMES.contains(&name)
+        || GENERATED_SUFFIXES
+            .iter()
+            .any(|suffix| ends_with_ignore_ascii_case(name, suffix))
+    {
+        return true;
+    }
+
+    match content {
+        Some(content) => is_generated_content(ext, &String::from_utf8_lossy(content)),
+        _ => false,
     }
-    false
 }
 
-/// Checks if the contents of a file starts with a shebang.
-pub(crate) fn has_shebang(data: &[u8]) -> bool {
-    data.starts_with(b"#!")
+/// Used internally to check the content of a file with the given extension for the rules of
+/// [`is_generated`].
+fn is_generated_content(ext: &str, content: &str) -> bool {
+    if content
+        .lines()
+        .take(GENERATED_SEARCH_LINES)
+        .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains(marker)))
+    {
+        return true;
+    }
+
+    if ext.eq_ignore_ascii_case("map") {
+        return content.trim_start().starts_with(r#"{"version":3"#);
+    }
+
+    if !ext.eq_ignore_ascii_case("js") && !ext.eq_ignore_ascii_case("css") {
+        return false;
+    }
+
+    let source_map = content.lines().rev().find(|line| !line.trim().is_empty());
+    if source_map.is_some_and(|line| {
+        line.starts_with("//# sourceMappingURL=") || line.starts_with("/*# sourceMappingURL=")
+    }) {
+        return true;
+    }
+
+    let lines = content.lines().count();
+    lines > 0 && content.len() / lines > MINIFIED_LINE_LENGTH
+}
+
+/// Used internally to check whether the given name ends with the given suffix, ignoring ASCII
+/// case.
+fn ends_with_ignore_ascii_case(name: &str, suffix: &str) -> bool {
+    name.len() >= suffix.len()
+        && name.as_bytes()[name.len() - suffix.len()..].eq_ignore_ascii_case(suffix.as_bytes())
+}
+
+/// Checks if the contents of a file starts with a shebang, i.e., `#!` followed by the absolute path
+/// of an interpreter. Optional whitespace before the path is allowed, so Rust's inner attributes
+/// like `#![no_std]` are not mistaken for a shebang.
+///
+/// # Example
+/// ```
+/// use linguist::utils::has_shebang;
+///
+/// assert!(has_shebang(b"#!/usr/bin/env python3\n"));
+/// assert!(has_shebang(b"#! /bin/sh\n"));
+/// assert!(!has_shebang(b"#![no_std]\n"));
+/// assert!(!has_shebang(b"fn main() {}\n"));
+/// ```
+pub fn has_shebang(data: &[u8]) -> bool {
+    match data.strip_prefix(b"#!") {
+        Some(rest) => rest.iter().find(|&&byte| byte != b' ' && byte != b'\t') == Some(&b'/'),
+        None => false,
+    }
 }
 
 /// Checks if the contents of a file starts with a shebang and the supplied interpreter.
-pub(crate) fn determine_multiline_exec(data: &[u8]) -> Option<String> {
-    let mut interpreter = "sh";
+pub(crate) fn determine_multiline_exec(data: &[u8]) -> String {
+    let mut interpreter = "sh".to_string();
     let mut cursor = Cursor::new(data);
     let mut buf = String::new();
 
@@ -110,35 +755,487 @@ pub(crate) fn determine_multiline_exec(data: &[u8]) -> Option<String> {
 
     for _i in 0..5 {
         buf.clear();
-        if let Ok(result) = cursor.read_line(&mut buf) {
-            if result == 0 {
-                break;
-            }
+        match cursor.read_line(&mut buf) {
+            Ok(0) => break,
+            Ok(_) => {}
+            // skip lines that are not valid UTF-8
+            Err(_) => continue,
         }
 
-        if shebang_exec.is_match(&buf) {
-            interpreter = shebang_exec.captures(&buf).unwrap().get(1).unwrap().into();
+        if let Some(exec) = shebang_exec.captures(&buf).and_then(|caps| caps.get(1)) {
+            interpreter = exec.as_str().to_string();
             break;
         }
     }
 
-    Some(interpreter.to_string())
+    interpreter
 }
 
-/// Checks whether the supplied input contains constructs that are not supported by the
-/// [regex crate](https://crates.io/crates/regex), e.g.:
-/// - lookbehind & lookahead
-/// - non-backtracking subexpressions
-/// - named & numbered capturing group / after text matching
-/// - backreference
-/// - possessive quantifier
+/// A `ShebangInfo` describes the interpreter that is referenced by a shebang, see
+/// [`parse_shebang`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShebangInfo {
+    /// The name of the interpreter without its path and minor version, e.g., `python3` for
+    /// `/usr/bin/python3.11`.
+    pub interpreter: String,
+    /// The arguments that follow the interpreter.
+    pub arguments: Vec<String>,
+}
+
+/// Parses the shebang at the beginning of the given content without any file IO. Like GitHub's
+/// Linguist, the path and the minor version of the interpreter are stripped, `env` and its
+/// options and assignments are skipped, and a `sh` script that restarts itself with
+/// `exec` on one of the next lines resolves to the executed interpreter. Returns `None` if the
+/// content does not start with a shebang, or if the interpreter cannot be determined, e.g., for
+/// `osascript -l`. The function never panics, whatever the input.
+///
+/// # Example
+/// ```
+/// use linguist::utils::parse_shebang;
+///
+/// let interpreter = |content: &[u8]| parse_shebang(content).map(|info| info.interpreter);
+///
+/// assert_eq!(interpreter(b"#!/usr/bin/env -S python3.11 -u\n").as_deref(), Some("python3"));
+/// assert_eq!(interpreter(b"#!/bin/sh\nexec tclsh \"$0\" \"$@\"\n").as_deref(), Some("tclsh"));
+/// assert_eq!(interpreter(b"#! /usr/bin/perl -w").as_deref(), Some("perl"));
+///
+/// for (shebang, expected) in [
+///     ("#!/usr/bin/ruby -w", "ruby"),
+///     ("#!/usr/local/bin/node --experimental-modules", "node"),
+///     ("#!/opt/python/bin/python3", "python3"),
+///     ("#!/usr/bin/python2.7 -O", "python2"),
+///     ("#!/usr/bin/ruby2.7", "ruby2"),
+///     ("#!/bin/bash -e", "bash"),
+///     ("#!/usr/bin/env -S node --no-warnings", "node"),
+///     ("#!/usr/bin/env PYTHONPATH=lib python3.12 -u", "python3"),
+///     ("#!/usr/bin/env -i LANG=C perl", "perl"),
+/// ] {
+///     assert_eq!(interpreter(shebang.as_bytes()).as_deref(), Some(expected), "{}", shebang);
+/// }
+///
+/// assert_eq!(interpreter(b"#!/usr/bin/env\n"), None);
+/// assert_eq!(interpreter(b"#!/usr/bin/env -S\n"), None);
+/// assert_eq!(interpreter(b"#!/usr/bin/env FOO=bar\n"), None);
+/// assert_eq!(interpreter(b"#!/\n"), None);
+/// assert_eq!(interpreter(b"#!"), None);
+/// assert_eq!(interpreter(b"#! \n"), None);
+/// assert_eq!(interpreter(b"#!/usr/bin/\n"), None);
+/// assert_eq!(interpreter(b"#![no_std]\n"), None);
+/// assert_eq!(interpreter(b"#!/usr/bin/osascript -l JavaScript\n"), None);
+/// ```
+pub fn parse_shebang(content: &[u8]) -> Option<ShebangInfo> {
+    let (line, rest) = match content.iter().position(|&byte| byte == b'\n') {
+        Some(pos) => (&content[..pos], &content[pos + 1..]),
+        None => (content, &content[content.len()..]),
+    };
+
+    // check whether the first line of the file is a shebang
+    if !has_shebang(line) {
+        return None;
+    }
+
+    let line = String::from_utf8_lossy(line);
+    let mut fields = line
+        .trim_start_matches("#!")
+        .split_whitespace()
+        .collect::<Vec<&str>>();
+    if fields.is_empty() {
+        return None;
+    }
+
+    if interpreter_name(fields[0])? == "env" {
+        // skip the options and variables of `env`, e.g., `-S` or `$FOO`
+        fields.remove(0);
+        while fields.first().is_some_and(|field| is_env_argument(field)) {
+            fields.remove(0);
+        }
+        if fields.is_empty() {
+            return None;
+        }
+    }
+
+    let mut interpreter = interpreter_name(fields[0])?.to_string();
+    let arguments: Vec<String> = fields[1..].iter().map(|arg| arg.to_string()).collect();
+
+    if interpreter == "sh" {
+        interpreter = determine_multiline_exec(rest);
+    }
+
+    // strip the minor version like GitHub's Linguist, e.g., `python3.11` or `ruby2.7`
+    if let Some((name, minor)) = interpreter.rsplit_once('.') {
+        if !name.is_empty() && !minor.is_empty() && minor.chars().all(|c| c.is_ascii_digit()) {
+            interpreter.truncate(name.len());
+        }
+    }
+
+    // If osascript is called with argument -l it could be different language so do not rely on it
+    // To match linguist behavior, see ref https://github.com/github/linguist/blob/d95bae794576ab0ef2fcb41a39eb61ea5302c5b5/lib/linguist/shebang.rb#L63
+    if interpreter == "osascript" && line.contains("-l") {
+        return None;
+    }
+
+    Some(ShebangInfo {
+        interpreter,
+        arguments,
+    })
+}
+
+/// Used internally to strip the path of an interpreter, e.g., `/usr/bin/env`. A path ending with
+/// a `/` names a directory, not an interpreter.
+fn interpreter_name(field: &str) -> Option<&str> {
+    if field.ends_with('/') {
+        return None;
+    }
+    Path::new(field).file_name().and_then(|name| name.to_str())
+}
+
+/// Used internally to check whether the given field is an option (`-S`), a variable (`$FOO`), or
+/// an assignment (`FOO=bar`) of `env`.
+fn is_env_argument(field: &str) -> bool {
+    if let Some((name, _)) = field.split_once('=') {
+        return !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    }
+    if let Some(option) = field.strip_prefix('-') {
+        return !option.is_empty() && option.chars().all(|c| c.is_ascii_alphabetic());
+    }
+    if let Some(variable) = field.strip_prefix('$') {
+        return !variable.is_empty()
+            && variable
+                .chars()
+                .all(|c| c.is_ascii_alphabetic() || c == '_');
+    }
+    false
+}
+
+/// The number of lines at the beginning and at the end of a file that are scanned for a modeline,
+/// like GitHub's Linguist does.
+pub const MODELINE_SEARCH_LINES: usize = 5;
+
+/// Parses an Emacs or Vim modeline in the first or last [`MODELINE_SEARCH_LINES`] lines of the
+/// given content, e.g., `-*- mode: python -*-` or `// vim: set ft=ruby:`, and returns the name of
+/// the language it sets. The name can be looked up with
+/// [`Container::get_language_by_name`](crate::container::Container::get_language_by_name), which
+/// also considers aliases. The function never panics, whatever the input.
+///
+/// # Example
+/// ```
+/// use linguist::utils::parse_modeline;
+///
+/// assert_eq!(parse_modeline("# -*- mode: ruby; coding: utf-8 -*-\n").as_deref(), Some("ruby"));
+/// assert_eq!(parse_modeline("#!/bin/sh\n# -*-python-*-\n").as_deref(), Some("python"));
+/// assert_eq!(parse_modeline("int x;\n/* vim: set ft=cpp: */\n").as_deref(), Some("cpp"));
+/// assert_eq!(parse_modeline("\" vim600: filetype=vim\n").as_deref(), Some("vim"));
+/// assert_eq!(parse_modeline("# vi: syntax=sh ts=4\n").as_deref(), Some("sh"));
+///
+/// assert_eq!(parse_modeline("let envim: set ft=ruby\n"), None);
+/// assert_eq!(parse_modeline("-*- coding: utf-8 -*-\n"), None);
+/// assert_eq!(parse_modeline("fn main() {}\n"), None);
+/// ```
+pub fn parse_modeline(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let head = MODELINE_SEARCH_LINES.min(lines.len());
+    // the last lines are not scanned twice if the content is short
+    let tail = lines.len().saturating_sub(MODELINE_SEARCH_LINES).max(head);
+
+    lines[..head]
+        .iter()
+        .chain(&lines[tail..])
+        .find_map(|line| emacs_modeline(line).or_else(|| vim_modeline(line)))
+}
+
+/// Used internally to parse an Emacs modeline, i.e., `-*- mode: python -*-` or `-*- python -*-`.
+fn emacs_modeline(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("-*-")?;
+    let (variables, _) = rest.split_once("-*-")?;
+
+    if !variables.contains(':') {
+        return modeline_value(variables);
+    }
+    variables.split(';').find_map(|variable| {
+        let (name, value) = variable.split_once(':')?;
+        if name.trim().eq_ignore_ascii_case("mode") {
+            modeline_value(value)
+        } else {
+            None
+        }
+    })
+}
+
+/// Used internally to parse a Vim modeline, i.e., `vim: set ft=ruby:` or `vi: filetype=ruby`.
+/// Like Vim, the marker has to start the line or follow a whitespace, and `vim` can be followed
+/// by a version, e.g., `vim600:`.
+fn vim_modeline(line: &str) -> Option<String> {
+    let options = line.char_indices().find_map(|(index, _)| {
+        if index > 0 && !line[..index].ends_with(char::is_whitespace) {
+            return None;
+        }
+        let rest = &line[index..];
+        let rest = ["vim", "vi", "ex"]
+            .iter()
+            .find_map(|marker| rest.strip_prefix(marker))?;
+        let rest = rest.strip_prefix(['<', '=', '>']).unwrap_or(rest);
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        rest.strip_prefix(':')
+    })?;
+
+    options
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .find_map(|option| {
+            let (name, value) = option.split_once('=')?;
+            match name {
+                "ft" | "filetype" | "syn" | "syntax" => modeline_value(value),
+                _ => None,
+            }
+        })
+}
+
+/// Used internally to extract the language name of a modeline value, which ends at the first
+/// character that cannot be part of a name, e.g., `;` or whitespace.
+fn modeline_value(value: &str) -> Option<String> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !(c.is_ascii_alphanumeric() || "+#-._".contains(c)))
+        .unwrap_or(value.len());
+
+    match &value[..end] {
+        "" => None,
+        name => Some(name.to_string()),
+    }
+}
+
+/// Checks whether the given pattern can be used by the [regex crate](https://crates.io/crates/regex).
+/// Returns [`LinguistError::UnsupportedPattern`] if the pattern uses an unsupported construct, or
+/// [`LinguistError::PatternCompileError`] if it cannot be compiled for another reason.
+///
+/// # Example
+/// ```
+/// use linguist::error::LinguistError;
+/// use linguist::utils::check_pattern;
+///
+/// assert!(check_pattern(r"(^|/)vendor/").is_ok());
+/// assert!(matches!(
+///     check_pattern(r"foo(?=bar)"),
+///     Err(LinguistError::UnsupportedPattern(pattern)) if pattern == "foo(?=bar)"
+/// ));
+/// assert!(matches!(check_pattern(r"foo("), Err(LinguistError::PatternCompileError(_))));
+/// ```
+pub fn check_pattern(pattern: &str) -> Result<(), LinguistError> {
+    if is_unsupported_regex_syntax(pattern) {
+        return Err(LinguistError::UnsupportedPattern(pattern.to_string()));
+    }
+
+    Regex::new(pattern)?;
+    Ok(())
+}
+
+/// An `UnsupportedConstruct` names a regular expression construct that is not supported by the
+/// [regex crate](https://crates.io/crates/regex), see [`unsupported_regex_construct`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnsupportedConstruct {
+    /// A positive or negative lookbehind, e.g., `(?<=a)` or `(?<!a)`.
+    Lookbehind,
+    /// A positive lookahead, e.g., `(?=a)`.
+    Lookahead,
+    /// A negative lookahead, e.g., `(?!a)`.
+    NegativeLookahead,
+    /// A non-backtracking subexpression, e.g., `(?>a)`.
+    AtomicGroup,
+    /// A reference to a previously matched group, e.g., `\1`.
+    Backreference,
+    /// A quantifier that does not backtrack, e.g., `a*+`.
+    PossessiveQuantifier,
+}
+
+impl std::fmt::Display for UnsupportedConstruct {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsupportedConstruct::Lookbehind => write!(f, "lookbehind"),
+            UnsupportedConstruct::Lookahead => write!(f, "lookahead"),
+            UnsupportedConstruct::NegativeLookahead => write!(f, "negative lookahead"),
+            UnsupportedConstruct::AtomicGroup => write!(f, "atomic group"),
+            UnsupportedConstruct::Backreference => write!(f, "backreference"),
+            UnsupportedConstruct::PossessiveQuantifier => write!(f, "possessive quantifier"),
+        }
+    }
+}
+
+/// Returns the first construct of the supplied input that is not supported by the
+/// [regex crate](https://crates.io/crates/regex). Escaped characters and character classes are
+/// skipped, e.g., `\(?=` and `[*+]` are fine.
 ///
 /// For a detailed reference on supported syntax see [RE2 Syntax](https://github.com/google/re2/wiki/Syntax)
-pub(crate) fn is_unsupported_regex_syntax(input: &str) -> bool {
-    input.contains("(?<")
-        || input.contains("(?=")
-        || input.contains("(?!")
-        || input.contains("(?>")
-        || input.contains("\\1")
-        || input.contains("*+")
+///
+/// # Example
+/// ```
+/// use linguist::utils::{unsupported_regex_construct, UnsupportedConstruct};
+///
+/// assert_eq!(
+///     unsupported_regex_construct(r"(?<!bower_)components/"),
+///     Some(UnsupportedConstruct::Lookbehind)
+/// );
+/// assert_eq!(
+///     unsupported_regex_construct(r"^\s*use\s+(?!v6)"),
+///     Some(UnsupportedConstruct::NegativeLookahead)
+/// );
+/// assert_eq!(
+///     unsupported_regex_construct(r"(['\x22])\w+\1"),
+///     Some(UnsupportedConstruct::Backreference)
+/// );
+/// assert_eq!(
+///     unsupported_regex_construct(r"\d++"),
+///     Some(UnsupportedConstruct::PossessiveQuantifier)
+/// );
+/// assert_eq!(unsupported_regex_construct(r"(?<name>\w+)\.[*+]\++"), None);
+/// ```
+pub fn unsupported_regex_construct(input: &str) -> Option<UnsupportedConstruct> {
+    let bytes = input.as_bytes();
+    let mut in_class = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &bytes[i + 1..];
+        match bytes[i] {
+            b'\\' => {
+                if !in_class && rest.first().is_some_and(|b| (b'1'..=b'9').contains(b)) {
+                    return Some(UnsupportedConstruct::Backreference);
+                }
+                // skip the escaped character
+                i += 1;
+            }
+            b'[' if !in_class => in_class = true,
+            b']' if in_class => in_class = false,
+            _ if in_class => {}
+            b'(' if rest.starts_with(b"?<=") || rest.starts_with(b"?<!") => {
+                return Some(UnsupportedConstruct::Lookbehind)
+            }
+            b'(' if rest.starts_with(b"?=") => return Some(UnsupportedConstruct::Lookahead),
+            b'(' if rest.starts_with(b"?!") => {
+                return Some(UnsupportedConstruct::NegativeLookahead)
+            }
+            b'(' if rest.starts_with(b"?>") => return Some(UnsupportedConstruct::AtomicGroup),
+            b'*' | b'+' | b'?' | b'}' if rest.first() == Some(&b'+') => {
+                return Some(UnsupportedConstruct::PossessiveQuantifier)
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Checks whether the supplied input contains constructs that are not supported by the
+/// [regex crate](https://crates.io/crates/regex), see [`unsupported_regex_construct`].
+pub fn is_unsupported_regex_syntax(input: &str) -> bool {
+    unsupported_regex_construct(input).is_some()
+}
+
+/// Languages that GitHub's Linguist lists as popular. They are marked as
+/// [`Language::popular`](crate::resolver::Language::popular) when GitHub's definitions are
+/// loaded, and win a tie with [`TieBreak::PreferPopular`](crate::resolver::TieBreak::PreferPopular).
+pub static POPULAR_LANGUAGES: [&str; 24] = [
+    "C",
+    "C#",
+    "C++",
+    "CoffeeScript",
+    "CSS",
+    "Dart",
+    "DM",
+    "Elixir",
+    "Go",
+    "Groovy",
+    "HTML",
+    "Java",
+    "JavaScript",
+    "Objective-C",
+    "Perl",
+    "PHP",
+    "PowerShell",
+    "Python",
+    "Ruby",
+    "Rust",
+    "Scala",
+    "Shell",
+    "Swift",
+    "TypeScript",
+];
+
+/// Checks whether the given language name is one of the [`POPULAR_LANGUAGES`], ignoring ASCII
+/// case.
+pub fn is_popular(name: &str) -> bool {
+    POPULAR_LANGUAGES
+        .iter()
+        .any(|popular| popular.eq_ignore_ascii_case(name))
+}
+
+/// Extensions of template files that wrap another language, e.g., `index.html.erb`.
+pub static TEMPLATE_EXTENSIONS: [&str; 10] = [
+    "erb",
+    "ejs",
+    "hbs",
+    "handlebars",
+    "mustache",
+    "tmpl",
+    "j2",
+    "jinja",
+    "liquid",
+    "twig",
+];
+
+/// Checks whether the given file is a template, i.e., whether its extension is one of the
+/// [`TEMPLATE_EXTENSIONS`].
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_template;
+///
+/// assert!(is_template("views/index.html.erb"));
+/// assert!(is_template("partials/header.hbs"));
+/// assert!(!is_template("src/main.rs"));
+/// ```
+pub fn is_template(file: impl AsRef<Path>) -> bool {
+    file.as_ref()
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| TEMPLATE_EXTENSIONS.contains(&ext))
+}
+
+pub static TEST_DIRECTORIES: [&str; 5] = ["test", "tests", "spec", "specs", "__tests__"];
+
+/// Checks if a file is a test file by checking whether it is located in a test directory or
+/// follows a common test file naming convention, e.g., `foo_test.go`, `test_foo.py`, or
+/// `foo.spec.ts`.
+///
+/// # Example
+/// ```
+/// use linguist::utils::is_test;
+///
+/// assert!(is_test("tests/resolver.rs"));
+/// assert!(is_test("pkg/server_test.go"));
+/// assert!(!is_test("src/main.rs"));
+/// ```
+pub fn is_test(file: impl AsRef<Path>) -> bool {
+    let file = file.as_ref();
+
+    if let Some(parent) = file.parent() {
+        for component in parent.components() {
+            if let Some(name) = component.as_os_str().to_str() {
+                if TEST_DIRECTORIES.contains(&name) {
+                    return true;
+                }
+            }
+        }
+    }
+
+    let name = match file.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name,
+        _ => return false,
+    };
+    let stem = name.split('.').next().unwrap_or_default();
+
+    stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.starts_with("test_")
+        || name.contains(".test.")
+        || name.contains(".spec.")
 }
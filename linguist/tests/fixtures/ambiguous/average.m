@@ -0,0 +1,4 @@
+% compute the arithmetic mean
+function m = average(x)
+  m = sum(x) / numel(x);
+end
@@ -0,0 +1,6 @@
+var count : int := 0
+loop
+    exit when count = 10
+    count := count + 1
+end loop
+put count
@@ -0,0 +1,5 @@
+use v6;
+use Test;
+
+plan 1;
+ok 1, "loads";
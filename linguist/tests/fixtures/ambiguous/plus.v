@@ -0,0 +1,4 @@
+Theorem plus_O_n : forall n : nat, 0 + n = n.
+Proof.
+  intros n. reflexivity.
+Qed.
@@ -0,0 +1,3 @@
+(* ::Package:: *)
+
+square[x_] := x^2
@@ -0,0 +1,3 @@
+int inflate(void *strm, int flush) {
+    return 0;
+}
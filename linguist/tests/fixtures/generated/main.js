@@ -0,0 +1,5 @@
+import { open } from "./widgets.js";
+
+document.querySelector("#widget").addEventListener("click", () => {
+  open(1);
+});
@@ -0,0 +1,7 @@
+/* A Bison parser, made by GNU Bison 3.8.2.  */
+
+/* Bison implementation for Yacc-like parsers in C */
+
+#define YYBISON 30802
+
+int yyparse (void);
@@ -0,0 +1,8 @@
+// tracking helpers shared by the apps
+#include <stdint.h>
+
+NS_ASSUME_NONNULL_BEGIN
+
+extern void track_event(const char *name, uint32_t value);
+
+NS_ASSUME_NONNULL_END
@@ -0,0 +1,4 @@
+#include <vector>
+
+template <typename T>
+T identity(T value);
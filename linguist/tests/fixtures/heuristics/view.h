@@ -0,0 +1,5 @@
+#import "Widget.h"
+
+@interface WidgetView : UIView
+@property (nonatomic, strong) Widget *widget;
+@end
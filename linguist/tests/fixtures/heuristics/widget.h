@@ -0,0 +1,7 @@
+#import <Foundation/Foundation.h>
+
+template <typename T>
+class Box;
+
+@interface Widget : NSObject
+@end
@@ -0,0 +1,5 @@
+#include <stddef.h>
+
+size_t length(const char *text);
+
+/* vim: set ft=cpp: */
@@ -0,0 +1,4 @@
+// @generated by the grammar compiler
+pub fn parse(input: &str) -> usize {
+    input.len()
+}
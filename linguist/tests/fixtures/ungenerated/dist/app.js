@@ -0,0 +1 @@
+!function(e,t){"use strict";var n=function(e){return document.querySelector(e)},r=function(e,t){return e.addEventListener("click",t)};e.w0=n("#widget-0"),r(e.w0,function(){t.open(0)});e.w1=n("#widget-1"),r(e.w1,function(){t.open(1)});e.w2=n("#widget-2"),r(e.w2,function(){t.open(2)});e.w3=n("#widget-3"),r(e.w3,function(){t.open(3)});e.w4=n("#widget-4"),r(e.w4,function(){t.open(4)});e.w5=n("#widget-5"),r(e.w5,function(){t.open(5)});e.w6=n("#widget-6"),r(e.w6,function(){t.open(6)});e.w7=n("#widget-7"),r(e.w7,function(){t.open(7)});e.w8=n("#widget-8"),r(e.w8,function(){t.open(8)});e.w9=n("#widget-9"),r(e.w9,function(){t.open(9)});e.w10=n("#widget-10"),r(e.w10,function(){t.open(10)});e.w11=n("#widget-11"),r(e.w11,function(){t.open(11)});}(window,{open:function(e){console.log("open",e)}});
@@ -0,0 +1,7 @@
+// a header whose distinguishing construct comes late
+#include <stddef.h>
+
+template <typename T>
+struct Box {
+    T value;
+};